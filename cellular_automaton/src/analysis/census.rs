@@ -0,0 +1,417 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Write as _,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use crate::{
+    automata::life::{Life, Rule, Soup},
+    automaton::Automaton,
+    cycle,
+    engine::Engine,
+    grid::{D4, Grid},
+    library::{self, Category},
+    scheme::UpdateScheme,
+    seeding::{Seed, Seeding},
+};
+
+// Recensement de soupes (à la manière d'apgsearch) pour une règle life-like : chaque soupe
+// tourne sur un tore jusqu'à ce qu'une configuration revienne, puis les objets restants
+// (composantes 8-connexes de deux générations superposées) sont isolés et classés par
+// apgcode (format de Catagolue). Sur un tore, les vaisseaux finissent par revenir, mais peuvent aussi
+// percuter les débris : le recensement diffère un peu de celui d'un plan infini.
+
+#[derive(Clone, Copy, Debug)]
+pub struct CensusConfig {
+    pub rule: Rule,
+    pub soups: usize,
+    // graine de la première soupe, les suivantes en +1
+    pub seed: u64,
+    // soupe k×k de densité donnée, au centre d'un tore de côté `torus`
+    pub soup: usize,
+    pub density: f32,
+    pub torus: usize,
+    // abandon d'une soupe qui ne s'est pas stabilisée
+    pub max_generations: u64,
+    pub threads: usize,
+}
+
+impl CensusConfig {
+    pub fn new(rule: Rule, soups: usize) -> Self {
+        Self {
+            rule,
+            soups,
+            seed: 0,
+            soup: 16,
+            density: 0.5,
+            torus: 64,
+            max_generations: 1 << 14,
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+}
+
+impl Default for CensusConfig {
+    fn default() -> Self {
+        Self::new(Rule::CONWAY, 1000)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Kind {
+    Still,
+    Oscillator(u64),
+    Spaceship(u64),
+    // pas de cycle une fois isolé (objet encore en interaction, ou trop long)
+    Unknown,
+}
+
+impl std::fmt::Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Kind::Still => write!(f, "still"),
+            Kind::Oscillator(p) => write!(f, "p{p}"),
+            Kind::Spaceship(p) => write!(f, "ship p{p}"),
+            Kind::Unknown => write!(f, "?"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Object {
+    // apgcode : xs<pop> (point fixe), xp<p> (oscillateur), xq<p> (vaisseau), suivi du
+    // Wechsler étendu minimal sur les phases et les 8 symétries ; zz<pop>_ (non classé,
+    // propre à ce recensement) suivi du Wechsler de l'objet tel quel
+    pub code: String,
+    pub kind: Kind,
+    pub population: usize,
+    pub name: Option<&'static str>,
+    pub count: u64,
+}
+
+#[derive(Clone, Debug)]
+pub struct Census {
+    pub config: CensusConfig,
+    // soupes sans cycle après `max_generations` (leurs objets ne sont pas comptés)
+    pub unstable: usize,
+    pub generations: u64,
+    // par fréquence décroissante
+    pub objects: Vec<Object>,
+}
+
+impl Census {
+    pub fn total(&self) -> u64 {
+        self.objects.iter().map(|o| o.count).sum()
+    }
+
+    pub fn to_table(&self) -> String {
+        let c = &self.config;
+        let mut out = format!(
+            "{}: {} soupes {}×{} (densité {}) sur tore {}×{}, {} générations, {} non stabilisées\n",
+            c.rule, c.soups, c.soup, c.soup, c.density, c.torus, c.torus, self.generations, self.unstable,
+        );
+        let total = self.total().max(1) as f64;
+        let _ = writeln!(out, "{:>10} {:>8}  {:<8} {:<40} name", "count", "share", "kind", "code");
+        for o in &self.objects {
+            let _ = writeln!(out, "{:>10} {:>7.3}%  {:<8} {:<40} {}",
+                o.count, 100.0 * o.count as f64 / total, o.kind.to_string(), o.code, o.name.unwrap_or(""));
+        }
+        out
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("code,kind,population,count,name\n");
+        for o in &self.objects {
+            let _ = writeln!(out, "{},{},{},{},{}", o.code, o.kind, o.population, o.count, o.name.unwrap_or(""));
+        }
+        out
+    }
+}
+
+// Règle life-like d'après un nom d'automate : "B36/S23", ou life, maze, mazectric...
+pub fn rule_by_name(name: &str) -> Option<Rule> {
+    [Life::conway(), Life::maze(), Life::mazectric()]
+        .into_iter()
+        .find(|l| l.name().eq_ignore_ascii_case(name))
+        .or_else(|| Life::parse(name))
+        .map(|l| l.life_rule())
+}
+
+fn engine(rule: Rule, w: usize, h: usize) -> Engine {
+    Engine::new(w, h, UpdateScheme::Synchronous, Box::new(Life::new(rule.to_string(), rule, Soup::Full(0.0))))
+}
+
+// Composantes 8-connexes (en tore) de `a` ∪ `b`, chacune recadrée avec les cellules de `a`.
+pub fn components(a: &Grid, b: &Grid) -> Vec<Grid> {
+    let (w, h) = (a.width() as isize, a.height() as isize);
+    let alive = |i: usize| a.as_slice()[i] != 0 || b.as_slice()[i] != 0;
+    let mut seen = vec![false; a.cell_count()];
+    let mut out = Vec::new();
+
+    for start in 0..a.cell_count() {
+        if seen[start] || !alive(start) {
+            continue;
+        }
+        // coordonnées non repliées, pour recadrer une composante à cheval sur le bord
+        seen[start] = true;
+        let mut cells = vec![((start as isize) % w, (start as isize) / w)];
+        let mut queue = VecDeque::from([cells[0]]);
+        while let Some((x, y)) = queue.pop_front() {
+            for (dx, dy) in [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)] {
+                let (nx, ny) = (x + dx, y + dy);
+                let j = (ny.rem_euclid(h) * w + nx.rem_euclid(w)) as usize;
+                if !seen[j] && alive(j) {
+                    seen[j] = true;
+                    cells.push((nx, ny));
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+
+        let x0 = cells.iter().map(|c| c.0).min().unwrap_or(0);
+        let y0 = cells.iter().map(|c| c.1).min().unwrap_or(0);
+        let x1 = cells.iter().map(|c| c.0).max().unwrap_or(0);
+        let y1 = cells.iter().map(|c| c.1).max().unwrap_or(0);
+        let mut g = Grid::new((x1 - x0 + 1) as usize, (y1 - y0 + 1) as usize);
+        for &(x, y) in &cells {
+            g.set((x - x0) as usize, (y - y0) as usize, a.get_wrapped(x, y));
+        }
+        if g.as_slice().iter().any(|&v| v != 0) {
+            out.push(g);
+        }
+    }
+    out
+}
+
+// Recadrage sur les cellules non nulles.
+fn crop(g: &Grid) -> Grid {
+    let (w, h) = (g.width(), g.height());
+    let live = |i: usize| g.as_slice()[i] != 0;
+    let xs = (0..w * h).filter(|&i| live(i)).map(|i| i % w);
+    let (x0, x1) = (xs.clone().min().unwrap_or(0), xs.max().unwrap_or(0));
+    let ys = (0..w * h).filter(|&i| live(i)).map(|i| i / w);
+    let (y0, y1) = (ys.clone().min().unwrap_or(0), ys.max().unwrap_or(0));
+    g.region(x0, y0, x1 - x0 + 1, y1 - y0 + 1)
+}
+
+// Wechsler étendu : bandes de 5 rangées séparées par `z`, une colonne par caractère (bit i
+// = rangée i de la bande, en base 32), zéros de fin de bande omis, suites de zéros abrégées
+// (`w` : 2, `x` : 3, `y` + caractère : 4 à 39).
+fn wechsler(g: &Grid) -> String {
+    const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut out = String::new();
+    for strip in 0..g.height().div_ceil(5) {
+        if strip > 0 {
+            out.push('z');
+        }
+        let mut zeros = 0;
+        for x in 0..g.width() {
+            let column = (0..5)
+                .filter(|&i| g.try_get(x, strip * 5 + i).is_some_and(|v| v != 0))
+                .fold(0, |c, i| c | 1 << i);
+            if column == 0 {
+                zeros += 1;
+                continue;
+            }
+            while zeros > 0 {
+                let n = zeros.min(39);
+                match n {
+                    1 => out.push('0'),
+                    2 => out.push('w'),
+                    3 => out.push('x'),
+                    _ => {
+                        out.push('y');
+                        out.push(DIGITS[n - 4] as char);
+                    }
+                }
+                zeros -= n;
+            }
+            out.push(DIGITS[column] as char);
+        }
+    }
+    out
+}
+
+// Forme minimale (longueur, puis ordre lexical) sur toutes les phases et symétries.
+fn canonical(phases: &[Grid]) -> String {
+    phases.iter()
+        .flat_map(|p| D4.iter().map(move |&t| wechsler(&crop(&p.transformed(t)))))
+        .min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+        .unwrap_or_default()
+}
+
+// Classe un objet isolé : il tourne seul jusqu'à revenir à une configuration déjà vue.
+// None s'il disparaît. Le compte reste à 0.
+pub fn classify(rule: Rule, object: &Grid) -> Option<Object> {
+    const PAD: usize = 16;
+    const MAX: u64 = 256;
+    let population = |g: &Grid| g.as_slice().iter().filter(|&&v| v != 0).count();
+    let unknown = || Some(Object {
+        code: format!("zz{}_{}", population(object), wechsler(&crop(object))),
+        kind: Kind::Unknown,
+        population: population(object),
+        name: None,
+        count: 0,
+    });
+
+    let mut e = engine(rule, object.width() + 2 * PAD, object.height() + 2 * PAD);
+    let mut g = Grid::new(object.width() + 2 * PAD, object.height() + 2 * PAD);
+    g.stamp(object, PAD, PAD, false);
+    if e.set_grid(g).is_err() {
+        return unknown();
+    }
+
+    let mut phases = vec![e.current().clone()];
+    while e.cycle().is_none() && e.finished().is_none() && e.generation() < MAX {
+        e.step_once();
+        phases.push(e.current().clone());
+    }
+    if population(e.current()) == 0 {
+        return None;
+    }
+    // phases du cycle : les `period` dernières configurations
+    let (kind, period) = match e.cycle() {
+        Some(c) if c.is_still() => (Kind::Still, 1),
+        Some(c) if c.is_spaceship() => (Kind::Spaceship(c.period), c.period),
+        Some(c) => (Kind::Oscillator(c.period), c.period),
+        // point fixe signalé par l'automate avant que la configuration ne revienne
+        None if e.finished().is_some() => (Kind::Still, 1),
+        None => return unknown(),
+    };
+    phases.drain(..phases.len() - period as usize);
+    let population = phases.iter().map(population).min().unwrap_or(0);
+    let prefix = match kind {
+        Kind::Oscillator(p) => format!("xp{p}"),
+        Kind::Spaceship(p) => format!("xq{p}"),
+        _ => format!("xs{population}"),
+    };
+    Some(Object { code: format!("{prefix}_{}", canonical(&phases)), kind, population, name: None, count: 0 })
+}
+
+// Noms des objets du catalogue fourni, par code (règle de Conway seulement).
+fn known_names(rule: Rule) -> HashMap<String, &'static str> {
+    if rule != Rule::CONWAY {
+        return HashMap::new();
+    }
+    library::CATALOGUE.iter()
+        .filter(|e| e.automaton == "life" && matches!(e.category, Category::StillLife | Category::Oscillator | Category::Spaceship))
+        .filter_map(|e| Some((classify(rule, &e.grid())?.code, e.name)))
+        .collect()
+}
+
+// Comptes d'un fil : code -> (type, population, nombre)
+#[derive(Default)]
+struct Tally {
+    objects: HashMap<String, (Kind, usize, u64)>,
+    unstable: usize,
+    generations: u64,
+}
+
+fn worker(config: &CensusConfig, next: &AtomicUsize) -> Tally {
+    let mut tally = Tally::default();
+    // une même forme revient très souvent (blocs, clignotants...) : classée une seule fois.
+    // Par hachage, les formes déjà vues et leur classement (comparées case à case en cas de collision).
+    let mut cache: HashMap<u64, Vec<(Grid, Option<Object>)>> = HashMap::new();
+    let mut e = engine(config.rule, config.torus, config.torus);
+
+    loop {
+        let i = next.fetch_add(1, Ordering::Relaxed);
+        if i >= config.soups {
+            break;
+        }
+        let seed = Seed::Soup(config.soup, config.density);
+        e.set_seeding(Some(Seeding::new(seed, config.seed.wrapping_add(i as u64))));
+        while e.cycle().is_none() && e.finished().is_none() && e.generation() < config.max_generations {
+            e.step_once();
+        }
+        tally.generations += e.generation();
+        if e.cycle().is_none() && e.finished().is_none() {
+            tally.unstable += 1;
+            continue;
+        }
+
+        // deux générations superposées : les parties d'un oscillateur restent ensemble
+        let now = e.current().clone();
+        e.step_once();
+        for object in components(&now, e.current()) {
+            let key = cycle::hash_grid(&object, &(object.width() as u64).to_le_bytes());
+            let seen = cache.entry(key).or_default();
+            let i = match seen.iter().position(|(g, _)| g.width() == object.width() && g.as_slice() == object.as_slice()) {
+                Some(i) => i,
+                None => {
+                    let o = classify(config.rule, &object);
+                    seen.push((object, o));
+                    seen.len() - 1
+                }
+            };
+            if let Some(o) = &seen[i].1 {
+                tally.objects.entry(o.code.clone()).or_insert((o.kind, o.population, 0)).2 += 1;
+            }
+        }
+    }
+    tally
+}
+
+pub fn run(config: &CensusConfig) -> Census {
+    let next = AtomicUsize::new(0);
+    let tallies: Vec<Tally> = thread::scope(|s| {
+        let handles: Vec<_> = (0..config.threads.max(1)).map(|_| s.spawn(|| worker(config, &next))).collect();
+        handles.into_iter().map(|h| h.join().expect("fil de recensement")).collect()
+    });
+
+    let mut merged: HashMap<String, (Kind, usize, u64)> = HashMap::new();
+    let (mut unstable, mut generations) = (0, 0);
+    for t in tallies {
+        unstable += t.unstable;
+        generations += t.generations;
+        for (code, (kind, population, count)) in t.objects {
+            merged.entry(code).or_insert((kind, population, 0)).2 += count;
+        }
+    }
+
+    let names = known_names(config.rule);
+    let mut objects: Vec<Object> = merged.into_iter()
+        .map(|(code, (kind, population, count))| Object { name: names.get(&code).copied(), code, kind, population, count })
+        .collect();
+    objects.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.code.cmp(&b.code)));
+    Census { config: *config, unstable, generations, objects }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::rle;
+
+    // codes de Catagolue
+    #[test]
+    fn apgcodes() {
+        for (rle, code, kind) in [
+            ("2o$2o!", "xs4_33", Kind::Still),
+            ("b2o$o2bo$b2o!", "xs6_696", Kind::Still),
+            ("2o$obo$bo!", "xs5_253", Kind::Still),
+            ("b2o$o2bo$bobo$2bo!", "xs7_2596", Kind::Still),
+            ("b2o$o2bo$o2bo$b2o!", "xs8_6996", Kind::Still),
+            ("3o!", "xp2_7", Kind::Oscillator(2)),
+            ("b3o$3o!", "xp2_7e", Kind::Oscillator(2)),
+            ("2o$2o$2b2o$2b2o!", "xp2_318c", Kind::Oscillator(2)),
+            ("bo$2bo$3o!", "xq4_153", Kind::Spaceship(4)),
+            ("bo2bo$o$o3bo$4o!", "xq4_6frc", Kind::Spaceship(4)),
+        ] {
+            let object = classify(Rule::CONWAY, &rle::from_rle(rle).expect("RLE valide")).expect("objet");
+            assert_eq!(object.code, code);
+            assert_eq!(object.kind, kind);
+        }
+    }
+
+    #[test]
+    fn wechsler_runs_and_strips() {
+        let line = |rle: &str| wechsler(&rle::from_rle(rle).expect("RLE valide"));
+        assert_eq!(line("o2bo3bo4bo!"), "1w1x1y01");
+        assert_eq!(line("o44bo!"), "1yzy11");
+        assert_eq!(line("o$o$o$o$o$o!"), "vz1");
+        assert_eq!(line("bo$5$bo!"), "01z02");
+        // bande vide entre les deux
+        assert_eq!(line("bo$10$bo!"), "01zz02");
+    }
+}
@@ -0,0 +1,335 @@
+use std::{
+    fmt::{self, Write as _},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use crate::{
+    analysis::spatial,
+    automata::life::{Life, Rule, Soup},
+    engine::Engine,
+    grid::Grid,
+    scheme::UpdateScheme,
+    seeding::{Seed, Seeding},
+};
+
+// Exploration d'un espace de règles B/S : chaque règle part des mêmes soupes (centrées sur
+// un tore) et chaque soupe est classée d'après sa périodicité, la croissance de sa population
+// et l'entropie des blocs 2×2 de la grille finale :
+// - meurt : plus aucune cellule vivante
+// - explosive : la population a au moins triplé et couvre le tore (seul le tore l'arrête,
+//   même si elle finit par se figer)
+// - fixe / périodique : une configuration revient (vaisseaux compris)
+// - chaotique : sans cycle avant la limite, grille désordonnée (entropie haute) ; si elle
+//   est ordonnée (débris et vaisseaux de longue période), la soupe compte comme périodique
+
+const EXPLOSIVE_GROWTH: f64 = 3.0;
+const CHAOTIC_ENTROPY: f64 = 0.3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Behaviour {
+    Dies,
+    Still,
+    Periodic,
+    Chaotic,
+    Explosive,
+}
+
+impl Behaviour {
+    pub const ALL: [Behaviour; 5] = [Behaviour::Dies, Behaviour::Still, Behaviour::Periodic, Behaviour::Chaotic, Behaviour::Explosive];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Behaviour::Dies => "dies",
+            Behaviour::Still => "still",
+            Behaviour::Periodic => "periodic",
+            Behaviour::Chaotic => "chaotic",
+            Behaviour::Explosive => "explosive",
+        }
+    }
+}
+
+impl fmt::Display for Behaviour {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+// Règles r telles que min ⊆ r ⊆ max (bit à bit, naissance et survie).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RuleRange {
+    pub min: Rule,
+    pub max: Rule,
+}
+
+impl RuleRange {
+    // Au-delà, l'exploration est refusée.
+    pub const MAX_RULES: usize = 1 << 12;
+
+    // "B3/S23..B36/S236", ou une règle seule.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let (a, b) = s.split_once("..").unwrap_or((s, s));
+        let rule = |r: &str| Rule::parse(r.trim()).ok_or_else(|| format!("règle invalide: {r}"));
+        let (min, max) = (rule(a)?, rule(b)?);
+        if min.birth & !max.birth != 0 || min.survive & !max.survive != 0 {
+            return Err(format!("{min} n'est pas incluse dans {max}"));
+        }
+        let range = Self { min, max };
+        if range.count() > Self::MAX_RULES {
+            return Err(format!("{} règles (au plus {})", range.count(), Self::MAX_RULES));
+        }
+        Ok(range)
+    }
+
+    // bits libres : naissance sur 0..9, survie sur 9..18
+    fn free(&self) -> u32 {
+        ((self.max.birth & !self.min.birth) as u32) | (((self.max.survive & !self.min.survive) as u32) << 9)
+    }
+
+    pub fn count(&self) -> usize {
+        1 << self.free().count_ones()
+    }
+
+    // Toutes les règles, en énumérant les sous-ensembles des bits libres.
+    pub fn rules(&self) -> Vec<Rule> {
+        let free = self.free();
+        let mut out = Vec::with_capacity(self.count());
+        let mut subset = 0u32;
+        loop {
+            out.push(Rule {
+                birth: self.min.birth | (subset & 0x1ff) as u16,
+                survive: self.min.survive | (subset >> 9) as u16,
+            });
+            if subset == free {
+                break;
+            }
+            subset = (subset.wrapping_sub(free)) & free;
+        }
+        out
+    }
+}
+
+impl fmt::Display for RuleRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.min, self.max)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ExploreConfig {
+    pub range: RuleRange,
+    // soupes par règle, graines seed, seed + 1...
+    pub soups: usize,
+    pub seed: u64,
+    pub soup: usize,
+    pub density: f32,
+    pub torus: usize,
+    pub generations: u64,
+    pub threads: usize,
+}
+
+impl ExploreConfig {
+    pub fn new(range: RuleRange) -> Self {
+        Self {
+            range,
+            soups: 4,
+            seed: 0,
+            soup: 16,
+            density: 0.5,
+            torus: 48,
+            generations: 1000,
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+}
+
+// Résultat d'une règle : comportement majoritaire et moyennes sur les soupes.
+#[derive(Clone, Copy, Debug)]
+pub struct RuleReport {
+    pub rule: Rule,
+    pub behaviour: Behaviour,
+    // soupes par comportement, dans l'ordre de `Behaviour::ALL`
+    pub counts: [usize; 5],
+    // population maximale / population initiale
+    pub growth: f64,
+    // part de cellules vivantes à la fin
+    pub density: f64,
+    // entropie des blocs 2×2 de la grille finale, en bits par cellule (de 0 à 1)
+    pub entropy: f64,
+    // plus longue période observée
+    pub period: Option<u64>,
+    // générations jusqu'au cycle (ou la limite)
+    pub generations: f64,
+}
+
+impl RuleReport {
+    pub const COLUMNS: [&'static str; 7] = ["rule", "behaviour", "growth", "density", "entropy", "period", "generations"];
+
+    // Tri sur la colonne `COLUMNS[column]`.
+    pub fn compare(&self, other: &Self, column: usize) -> std::cmp::Ordering {
+        match column {
+            0 => (self.rule.birth, self.rule.survive).cmp(&(other.rule.birth, other.rule.survive)),
+            1 => self.behaviour.cmp(&other.behaviour),
+            2 => self.growth.total_cmp(&other.growth),
+            3 => self.density.total_cmp(&other.density),
+            4 => self.entropy.total_cmp(&other.entropy),
+            5 => self.period.cmp(&other.period),
+            _ => self.generations.total_cmp(&other.generations),
+        }
+    }
+}
+
+struct SoupResult {
+    behaviour: Behaviour,
+    growth: f64,
+    density: f64,
+    entropy: f64,
+    period: Option<u64>,
+    generations: u64,
+}
+
+fn run_soup(engine: &mut Engine, config: &ExploreConfig, seed: u64) -> SoupResult {
+    engine.set_seeding(Some(Seeding::new(Seed::Soup(config.soup, config.density), seed)));
+    let population = |g: &Grid| g.as_slice().iter().filter(|&&v| v != 0).count();
+    let initial = population(engine.current()).max(1);
+    let mut peak = initial;
+    while engine.cycle().is_none() && engine.finished().is_none() && engine.generation() < config.generations {
+        engine.step_once();
+        peak = peak.max(population(engine.current()));
+    }
+
+    let g = engine.current();
+    let alive = population(g);
+    let growth = peak as f64 / initial as f64;
+    let entropy = spatial::block_entropy(g, 2);
+    // à un moment de la soupe, les cellules vivantes ont atteint tous les bords du tore
+    let covers = engine.stats().samples().iter().filter_map(|s| s.bbox)
+        .any(|(x0, y0, x1, y1)| x1 - x0 + 1 == g.width() && y1 - y0 + 1 == g.height());
+    let period = engine.cycle().map(|c| c.period);
+
+    let behaviour = match engine.cycle() {
+        _ if alive == 0 => Behaviour::Dies,
+        _ if growth >= EXPLOSIVE_GROWTH && covers => Behaviour::Explosive,
+        Some(c) if c.is_still() => Behaviour::Still,
+        Some(_) => Behaviour::Periodic,
+        // point fixe signalé par l'automate avant que la configuration ne revienne
+        None if engine.finished().is_some() => Behaviour::Still,
+        None if entropy >= CHAOTIC_ENTROPY => Behaviour::Chaotic,
+        None => Behaviour::Periodic,
+    };
+    SoupResult { behaviour, growth, density: alive as f64 / g.cell_count() as f64, entropy, period, generations: engine.generation() }
+}
+
+pub fn explore_rule(engine: &mut Engine, config: &ExploreConfig, rule: Rule) -> RuleReport {
+    let life = Life::new(rule.to_string(), rule, Soup::Full(0.0));
+    engine.set_automaton(Box::new(life)).expect("tore assez grand pour une règle life-like");
+
+    let results: Vec<SoupResult> = (0..config.soups.max(1))
+        .map(|i| run_soup(engine, config, config.seed.wrapping_add(i as u64)))
+        .collect();
+    let mut counts = [0; 5];
+    for r in &results {
+        counts[r.behaviour as usize] += 1;
+    }
+    let mean = |f: fn(&SoupResult) -> f64| results.iter().map(f).sum::<f64>() / results.len() as f64;
+    // à égalité, le comportement le plus actif l'emporte
+    let behaviour = Behaviour::ALL.into_iter().max_by_key(|b| counts[*b as usize]).unwrap_or(Behaviour::Dies);
+
+    RuleReport {
+        rule,
+        behaviour,
+        counts,
+        growth: mean(|r| r.growth),
+        density: mean(|r| r.density),
+        entropy: mean(|r| r.entropy),
+        period: results.iter().filter_map(|r| r.period).max(),
+        generations: mean(|r| r.generations as f64),
+    }
+}
+
+// Explore toutes les règles de l'intervalle sur `threads` fils ; `done` compte les règles finies.
+pub fn run(config: &ExploreConfig, done: &AtomicUsize) -> Vec<RuleReport> {
+    let rules = config.range.rules();
+    let next = AtomicUsize::new(0);
+    let mut reports: Vec<RuleReport> = thread::scope(|s| {
+        let handles: Vec<_> = (0..config.threads.max(1)).map(|_| s.spawn(|| {
+            let mut engine = Engine::new(config.torus, config.torus, UpdateScheme::Synchronous, Box::new(Life::conway()));
+            let mut out = Vec::new();
+            loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(&rule) = rules.get(i) else { break };
+                out.push(explore_rule(&mut engine, config, rule));
+                done.fetch_add(1, Ordering::Relaxed);
+            }
+            out
+        })).collect();
+        handles.into_iter().flat_map(|h| h.join().expect("fil d'exploration")).collect()
+    });
+    reports.sort_by(|a, b| a.compare(b, 0));
+    reports
+}
+
+pub fn to_table(reports: &[RuleReport]) -> String {
+    let mut out = format!("{:<22} {:<10} {:>7} {:>7} {:>7} {:>7} {:>7}  dies/still/periodic/chaotic/explosive\n",
+        "rule", "behaviour", "growth", "density", "entropy", "period", "gens");
+    for r in reports {
+        let counts: Vec<String> = r.counts.iter().map(|c| c.to_string()).collect();
+        let _ = writeln!(out, "{:<22} {:<10} {:>7.2} {:>7.3} {:>7.3} {:>7} {:>7.0}  {}",
+            r.rule.to_string(), r.behaviour.name(), r.growth, r.density, r.entropy,
+            r.period.map_or(String::new(), |p| p.to_string()), r.generations, counts.join("/"));
+    }
+    out
+}
+
+pub fn to_csv(reports: &[RuleReport]) -> String {
+    let mut out = String::from("rule,behaviour,dies,still,periodic,chaotic,explosive,growth,density,entropy,period,generations\n");
+    for r in reports {
+        let [a, b, c, d, e] = r.counts;
+        let _ = writeln!(out, "{},{},{a},{b},{c},{d},{e},{:.4},{:.4},{:.4},{},{:.1}",
+            r.rule, r.behaviour, r.growth, r.density, r.entropy, r.period.map_or(String::new(), |p| p.to_string()), r.generations);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(range: &str) -> Vec<String> {
+        RuleRange::parse(range).expect("intervalle valide").rules().iter().map(Rule::to_string).collect()
+    }
+
+    #[test]
+    fn range_enumeration() {
+        assert_eq!(names("B3/S23..B36/S23"), ["B3/S23", "B36/S23"]);
+        assert_eq!(names("B3/S23"), ["B3/S23"]);
+        assert_eq!(names("B3/S23..B368/S238"), [
+            "B3/S23", "B36/S23", "B38/S23", "B368/S23", "B3/S238", "B36/S238", "B38/S238", "B368/S238",
+        ]);
+        let range = RuleRange::parse("B/S..B012/S0").expect("intervalle valide");
+        assert_eq!(range.count(), 16);
+        assert_eq!(range.rules().len(), 16);
+
+        assert!(RuleRange::parse("B36/S23..B3/S23").is_err());
+        assert!(RuleRange::parse("B3/S23..B3/X").is_err());
+        assert!(RuleRange::parse("B/S..B012345678/S012345678").is_err());
+    }
+
+    #[test]
+    fn classification() {
+        let config = ExploreConfig { threads: 1, ..ExploreConfig::new(RuleRange::parse("B3/S23").expect("règle")) };
+        let mut engine = Engine::new(config.torus, config.torus, UpdateScheme::Synchronous, Box::new(Life::conway()));
+        let report = |engine: &mut Engine, r: &str| explore_rule(engine, &config, Rule::parse(r).expect("règle"));
+
+        // graines 0..4 : une soupe figée, deux périodiques, une encore active à 1000 générations
+        let life = report(&mut engine, "B3/S23");
+        assert_eq!(life.behaviour, Behaviour::Periodic);
+        assert_eq!(life.counts, [0, 1, 2, 1, 0]);
+        assert_eq!(life.period, Some(2));
+
+        assert_eq!(report(&mut engine, "B/S").behaviour, Behaviour::Dies);
+        let fill = report(&mut engine, "B1/S012345678");
+        assert_eq!(fill.behaviour, Behaviour::Explosive);
+        assert_eq!(fill.counts, [0, 0, 0, 0, 4]);
+    }
+}
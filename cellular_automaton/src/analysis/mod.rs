@@ -0,0 +1,5 @@
+pub mod census;
+pub mod explore;
+pub mod path;
+pub mod spatial;
+pub mod sweep;
@@ -0,0 +1,282 @@
+use std::collections::VecDeque;
+
+use crate::{
+    automata::{self, mazegenerator::{MazeAlgorithm, MazeGenerator}},
+    engine::{Engine, Until},
+    grid::Grid,
+    scheme::UpdateScheme,
+};
+
+// Analyse du résultat de `MazeSolver` (valeurs : 1 mur, 2 départ, 3 arrivée, 7 trajet).
+// Voisinage 4 en tore, comme le solveur.
+
+pub type Pos = (usize, usize);
+
+#[derive(Clone, Debug, Default)]
+pub struct PathReport {
+    // départ, cases 7..., arrivée
+    pub path: Option<Vec<Pos>>,
+    // plus court chemin de référence (BFS sur toutes les cases hors murs), en pas
+    pub shortest: Option<usize>,
+    // le trajet relie départ et arrivée, et est aussi court que la référence
+    pub connected: bool,
+    pub optimal: bool,
+    // cases 7 qui ne sont pas sur le trajet
+    pub stray: usize,
+    // impasses (cases libres à une seule ouverture) et cases remplies par comblement des impasses
+    pub dead_ends: usize,
+    pub dead_end_cells: usize,
+}
+
+impl PathReport {
+    // Longueur du trajet en pas.
+    pub fn length(&self) -> Option<usize> {
+        self.path.as_ref().map(|p| p.len() - 1)
+    }
+
+    pub fn to_json(&self) -> String {
+        let opt = |v: Option<usize>| v.map_or("null".to_string(), |v| v.to_string());
+        let path = match &self.path {
+            Some(p) => format!("[{}]", p.iter().map(|(x, y)| format!("[{x},{y}]")).collect::<Vec<_>>().join(",")),
+            None => "null".to_string(),
+        };
+        format!(
+            "{{\"connected\":{},\"optimal\":{},\"length\":{},\"shortest\":{},\"stray\":{},\"dead_ends\":{},\"dead_end_cells\":{},\"path\":{}}}",
+            self.connected, self.optimal, opt(self.length()), opt(self.shortest),
+            self.stray, self.dead_ends, self.dead_end_cells, path,
+        )
+    }
+}
+
+fn neighbors(g: &Grid, (x, y): Pos) -> [Pos; 4] {
+    let w = g.width();
+    let h = g.height();
+    let xm1 = if x == 0 { w - 1 } else { x - 1 };
+    let xp1 = if x + 1 == w { 0 } else { x + 1 };
+    let ym1 = if y == 0 { h - 1 } else { y - 1 };
+    let yp1 = if y + 1 == h { 0 } else { y + 1 };
+    [(x, ym1), (xm1, y), (xp1, y), (x, yp1)]
+}
+
+pub fn find(g: &Grid, value: u8) -> Option<Pos> {
+    let i = g.as_slice().iter().position(|&v| v == value)?;
+    Some((i % g.width(), i / g.width()))
+}
+
+// Plus court chemin de `from` à `to` en ne passant que par les cases acceptées par `pass`.
+fn bfs(g: &Grid, from: Pos, to: Pos, pass: impl Fn(Pos, u8) -> bool) -> Option<Vec<Pos>> {
+    let w = g.width();
+    let mut parent = vec![usize::MAX; w * g.height()];
+    let mut queue = VecDeque::from([from]);
+    parent[from.1 * w + from.0] = from.1 * w + from.0;
+
+    while let Some(p) = queue.pop_front() {
+        if p == to {
+            let mut path = vec![p];
+            let mut i = p.1 * w + p.0;
+            while parent[i] != i {
+                i = parent[i];
+                path.push((i % w, i / w));
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for n in neighbors(g, p) {
+            let j = n.1 * w + n.0;
+            if parent[j] == usize::MAX && pass(n, g.get(n.0, n.1)) {
+                parent[j] = p.1 * w + p.0;
+                queue.push_back(n);
+            }
+        }
+    }
+    None
+}
+
+// Trajet marqué par le solveur : départ -> cases 7 -> arrivée (la première case 3 : les
+// autres ne servent pas de passage).
+pub fn extract_path(g: &Grid) -> Option<Vec<Pos>> {
+    let start = find(g, 2)?;
+    let goal = find(g, 3)?;
+    bfs(g, start, goal, |p, v| v == 7 || p == goal)
+}
+
+// Solution de référence : BFS sur toutes les cases qui ne sont pas des murs.
+pub fn shortest_path(g: &Grid) -> Option<Vec<Pos>> {
+    let start = find(g, 2)?;
+    let goal = find(g, 3)?;
+    bfs(g, start, goal, |_, v| v != 1)
+}
+
+pub fn analyse(g: &Grid) -> PathReport {
+    let path = extract_path(g);
+    let shortest = shortest_path(g).map(|p| p.len() - 1);
+    let connected = path.is_some();
+    let optimal = matches!((&path, shortest), (Some(p), Some(s)) if p.len() - 1 == s);
+    let marked = g.as_slice().iter().filter(|&&v| v == 7).count();
+    let on_path = path.as_ref().map_or(0, |p| p.iter().filter(|&&(x, y)| g.get(x, y) == 7).count());
+    let stray = marked - on_path;
+
+    let (dead_ends, dead_end_cells) = dead_end_stats(g);
+    PathReport { path, shortest, connected, optimal, stray, dead_ends, dead_end_cells }
+}
+
+// Compte les impasses, puis les comble de proche en proche (sans toucher départ et arrivée).
+fn dead_end_stats(g: &Grid) -> (usize, usize) {
+    let w = g.width();
+    let open = |v: u8| v != 1;
+    let keep = |v: u8| v == 2 || v == 3;
+
+    let mut degree = vec![0u8; g.cell_count()];
+    let mut queue = Vec::new();
+    for y in 0..g.height() {
+        for x in 0..w {
+            if !open(g.get(x, y)) { continue; }
+            let d = neighbors(g, (x, y)).iter().filter(|n| open(g.get(n.0, n.1))).count() as u8;
+            degree[y * w + x] = d;
+            if d == 1 && !keep(g.get(x, y)) {
+                queue.push((x, y));
+            }
+        }
+    }
+    let dead_ends = queue.len();
+
+    let mut filled = vec![false; g.cell_count()];
+    let mut count = 0;
+    while let Some(p) = queue.pop() {
+        let i = p.1 * w + p.0;
+        if filled[i] { continue; }
+        filled[i] = true;
+        count += 1;
+        for n in neighbors(g, p) {
+            let j = n.1 * w + n.0;
+            let v = g.get(n.0, n.1);
+            if open(v) && !filled[j] {
+                degree[j] -= 1;
+                if degree[j] <= 1 && !keep(v) {
+                    queue.push(n);
+                }
+            }
+        }
+    }
+    (dead_ends, count)
+}
+
+// Vérification sur des labyrinthes aléatoires : chaque algorithme de génération tour à tour,
+// puis le solveur. Le trajet doit toujours relier départ et arrivée, et être optimal pour les
+// labyrinthes parfaits (sans boucle, donc à chemin unique). Le labyrinthe i est tiré avec la
+// graine seed + i. Renvoie les échecs.
+pub fn check_random_mazes(runs: usize, w: usize, h: usize, seed: u64) -> Vec<String> {
+    let mut failures = Vec::new();
+
+    for i in 0..runs {
+        let algorithm = MazeAlgorithm::ALL[i % MazeAlgorithm::ALL.len()];
+        let Some(solver) = automata::by_name("mazesolver") else { break };
+
+        let generator = MazeGenerator::with_algorithm(algorithm).with_seed(seed.wrapping_add(i as u64));
+        let mut engine = Engine::new(w, h, UpdateScheme::Synchronous, Box::new(generator.clone()));
+        if let Err(e) = engine.chain(Box::new(generator), Until::FinishedOr(u64::MAX), solver) {
+            failures.push(format!("#{i} {}: {e}", algorithm.name()));
+            continue;
+        }
+
+        let limit = 20 * (w * h) as u64;
+        let mut steps = 0;
+        while engine.finished().is_none() && steps < limit {
+            engine.step_once();
+            steps += 1;
+        }
+
+        let report = analyse(engine.current());
+        let perfect = !matches!(algorithm, MazeAlgorithm::Braided(_));
+        let problem = if engine.finished().is_none() {
+            Some("pas terminé".to_string())
+        } else if !report.connected {
+            Some("trajet non connexe".to_string())
+        } else if perfect && !report.optimal {
+            Some(format!("trajet {:?} au lieu de {:?}", report.length(), report.shortest))
+        } else {
+            None
+        };
+
+        if let Some(p) = problem {
+            failures.push(format!("#{i} {}: {p}", algorithm.name()));
+        }
+    }
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(w: usize, cells: &[u8]) -> Grid {
+        let mut g = Grid::new(w, cells.len() / w);
+        g.as_mut_slice().copy_from_slice(cells);
+        g
+    }
+
+    // un labyrinthe par algorithme
+    #[test]
+    fn random_mazes_are_solved() {
+        assert_eq!(check_random_mazes(MazeAlgorithm::ALL.len(), 21, 15, 0), Vec::<String>::new());
+    }
+
+    // version longue : cargo test -- --ignored
+    #[test]
+    #[ignore]
+    fn many_random_mazes_are_solved() {
+        for seed in [0, 1, 42] {
+            assert_eq!(check_random_mazes(20, 21, 15, seed), Vec::<String>::new(), "graine {seed}");
+        }
+    }
+
+    #[test]
+    fn marked_path() {
+        // départ, deux cases 7, arrivée ; un 7 isolé hors trajet
+        let g = grid(5, &[
+            1, 1, 1, 1, 1,
+            2, 7, 7, 3, 1,
+            1, 0, 1, 1, 7,
+            1, 1, 1, 1, 1,
+        ]);
+        let r = analyse(&g);
+        assert!(r.connected);
+        assert_eq!(r.length(), Some(3));
+        assert_eq!(r.shortest, Some(3));
+        assert!(r.optimal);
+        assert_eq!(r.stray, 1);
+    }
+
+    #[test]
+    fn no_path() {
+        let g = grid(5, &[
+            2, 7, 1, 3, 1,
+            1, 1, 1, 1, 1,
+        ]);
+        let r = analyse(&g);
+        assert!(!r.connected);
+        assert_eq!(r.path, None);
+        assert_eq!(r.stray, 1);
+    }
+
+    #[test]
+    fn several_goals() {
+        // plusieurs cases 3 : le trajet ne passe pas par une arrivée pour en joindre une autre
+        let g = grid(3, &[
+            1, 1, 3,
+            2, 3, 3,
+        ]);
+        let r = analyse(&g);
+        assert_eq!(r.stray, 0);
+        assert!(!r.connected);
+        assert_eq!(r.path, None);
+
+        let g = grid(4, &[
+            2, 7, 3, 3,
+            1, 1, 3, 1,
+        ]);
+        let r = analyse(&g);
+        assert_eq!(r.path, Some(vec![(0, 0), (1, 0), (2, 0)]));
+        assert_eq!(r.stray, 0);
+    }
+}
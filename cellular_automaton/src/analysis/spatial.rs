@@ -0,0 +1,268 @@
+use std::{collections::HashMap, fmt::Write as _, sync::atomic::{AtomicUsize, Ordering}};
+
+use crate::{automaton::Automaton, engine::{Engine, SizeError}, grid::Grid, scheme::UpdateScheme};
+
+// Mesures d'ordre / désordre d'une grille : entropies, corrélation spatiale, tailles des
+// amas, et propagation d'un défaut entre deux exécutions (même hasard, une cellule d'écart).
+// Tout est calculé en tore, comme les automates.
+
+// Entropie de Shannon (bits) d'une suite de fréquences.
+fn shannon(counts: impl Iterator<Item = usize>, total: usize) -> f64 {
+    counts.filter(|&c| c > 0)
+        .map(|c| c as f64 / total as f64)
+        .fold(0.0, |h, p| h - p * p.log2())
+}
+
+// Entropie de la distribution des états, en bits par cellule.
+pub fn cell_entropy(g: &Grid) -> f64 {
+    let mut counts = [0usize; 256];
+    for &v in g.as_slice() {
+        counts[v as usize] += 1;
+    }
+    shannon(counts.into_iter(), g.cell_count())
+}
+
+// Entropie des blocs k×k (toutes positions), ramenée en bits par cellule.
+pub fn block_entropy(g: &Grid, k: usize) -> f64 {
+    let mut counts: HashMap<Vec<u8>, usize> = HashMap::new();
+    for y in 0..g.height() as isize {
+        for x in 0..g.width() as isize {
+            let block = (0..k * k).map(|i| g.get_wrapped(x + (i % k) as isize, y + (i / k) as isize)).collect();
+            *counts.entry(block).or_insert(0) += 1;
+        }
+    }
+    shannon(counts.into_values(), g.cell_count()) / (k * k) as f64
+}
+
+// Autocorrélation de l'occupation (état non nul) à distance r = 1..=max_r, moyenne des
+// décalages horizontal et vertical. 1 : voisins identiques, 0 : indépendants.
+pub fn correlation(g: &Grid, max_r: usize) -> Vec<f64> {
+    let s = |x: isize, y: isize| (g.get_wrapped(x, y) != 0) as u8 as f64;
+    let n = g.cell_count() as f64;
+    let mean = g.as_slice().iter().filter(|&&v| v != 0).count() as f64 / n;
+    let variance = mean - mean * mean;
+
+    (1..=max_r as isize).map(|r| {
+        if variance <= 0.0 {
+            return 0.0;
+        }
+        let mut sum = 0.0;
+        for y in 0..g.height() as isize {
+            for x in 0..g.width() as isize {
+                sum += s(x, y) * (s(x + r, y) + s(x, y + r)) / 2.0;
+            }
+        }
+        (sum / n - mean * mean) / variance
+    }).collect()
+}
+
+// Première distance où la corrélation passe sous 1/e (None si jamais avant `correlation.len()`).
+pub fn correlation_length(correlation: &[f64]) -> Option<usize> {
+    correlation.iter().position(|&c| c < (-1.0f64).exp()).map(|i| i + 1)
+}
+
+// Amas : cellules de même état non nul reliées par leurs 4 voisins.
+#[derive(Clone, Debug, Default)]
+pub struct Clusters {
+    // taille -> nombre d'amas, par taille croissante
+    pub histogram: Vec<(usize, usize)>,
+    pub count: usize,
+    pub largest: usize,
+    pub mean: f64,
+}
+
+pub fn clusters(g: &Grid) -> Clusters {
+    let (w, h) = (g.width() as isize, g.height() as isize);
+    let cells = g.as_slice();
+    let mut seen = vec![false; cells.len()];
+    let mut sizes: HashMap<usize, usize> = HashMap::new();
+    let mut stack = Vec::new();
+
+    for start in 0..cells.len() {
+        if seen[start] || cells[start] == 0 {
+            continue;
+        }
+        seen[start] = true;
+        stack.push(start);
+        let mut size = 0;
+        while let Some(i) = stack.pop() {
+            size += 1;
+            let (x, y) = (i as isize % w, i as isize / w);
+            for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+                let j = ((y + dy).rem_euclid(h) * w + (x + dx).rem_euclid(w)) as usize;
+                if !seen[j] && cells[j] == cells[start] {
+                    seen[j] = true;
+                    stack.push(j);
+                }
+            }
+        }
+        *sizes.entry(size).or_insert(0) += 1;
+    }
+
+    let mut histogram: Vec<(usize, usize)> = sizes.into_iter().collect();
+    histogram.sort();
+    let count = histogram.iter().map(|h| h.1).sum();
+    let cells_in = histogram.iter().map(|(s, n)| s * n).sum::<usize>();
+    Clusters {
+        largest: histogram.last().map_or(0, |h| h.0),
+        mean: if count == 0 { 0.0 } else { cells_in as f64 / count as f64 },
+        histogram,
+        count,
+    }
+}
+
+// Distance de Hamming normalisée (part des cellules différentes).
+pub fn hamming(a: &Grid, b: &Grid) -> f64 {
+    let diff = a.as_slice().iter().zip(b.as_slice()).filter(|(x, y)| x != y).count();
+    diff as f64 / a.cell_count().max(1) as f64
+}
+
+// Propagation d'un défaut : deux exécutions depuis `grid`, la seconde avec la cellule (x, y)
+// changée d'état (v + 1 mod k, les états hors de l'automate comptant comme k - 1), sous le même
+// schéma et la même graine (mêmes tirages). Renvoie la distance de Hamming après chaque pas
+// (0 en premier : la distance initiale). `done` compte les pas faits.
+pub fn damage_spreading(
+    automaton: &dyn Automaton,
+    grid: &Grid,
+    scheme: UpdateScheme,
+    seed: u64,
+    (x, y): (usize, usize),
+    steps: usize,
+    done: &AtomicUsize,
+) -> Result<Vec<f64>, SizeError> {
+    let states = automaton.states().len().clamp(2, 256) as u16;
+    let mut damaged = grid.clone();
+    let v = (grid.get(x, y) as u16).min(states - 1);
+    damaged.set(x, y, ((v + 1) % states) as u8);
+
+    let mut runs = Vec::with_capacity(2);
+    for g in [grid.clone(), damaged] {
+        let mut e = Engine::with_grid(g, scheme, automaton.box_clone())?;
+        e.set_seed(seed);
+        runs.push(e);
+    }
+
+    let mut out = Vec::with_capacity(steps + 1);
+    out.push(hamming(runs[0].current(), runs[1].current()));
+    for _ in 0..steps {
+        for e in &mut runs {
+            e.step_once();
+        }
+        out.push(hamming(runs[0].current(), runs[1].current()));
+        done.fetch_add(1, Ordering::Relaxed);
+    }
+    Ok(out)
+}
+
+// Toutes les mesures d'une grille.
+#[derive(Clone, Debug, Default)]
+pub struct SpatialReport {
+    pub cell_entropy: f64,
+    pub block_entropy2: f64,
+    pub block_entropy3: f64,
+    // r = 1, 2...
+    pub correlation: Vec<f64>,
+    pub correlation_length: Option<usize>,
+    pub clusters: Clusters,
+}
+
+impl SpatialReport {
+    pub fn measure(g: &Grid) -> Self {
+        let correlation = correlation(g, (g.width().min(g.height()) / 2).min(16));
+        Self {
+            cell_entropy: cell_entropy(g),
+            block_entropy2: block_entropy(g, 2),
+            block_entropy3: block_entropy(g, 3),
+            correlation_length: correlation_length(&correlation),
+            correlation,
+            clusters: clusters(g),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let list = |v: &mut dyn Iterator<Item = String>| v.collect::<Vec<_>>().join(", ");
+        let mut out = String::from("{\n");
+        let _ = writeln!(out, "  \"cell_entropy\": {:.6},", self.cell_entropy);
+        let _ = writeln!(out, "  \"block_entropy_2x2\": {:.6},", self.block_entropy2);
+        let _ = writeln!(out, "  \"block_entropy_3x3\": {:.6},", self.block_entropy3);
+        let _ = writeln!(out, "  \"correlation\": [{}],", list(&mut self.correlation.iter().map(|c| format!("{c:.6}"))));
+        let _ = writeln!(out, "  \"correlation_length\": {},", self.correlation_length.map_or("null".to_string(), |l| l.to_string()));
+        let _ = writeln!(out, "  \"clusters\": {},", self.clusters.count);
+        let _ = writeln!(out, "  \"largest_cluster\": {},", self.clusters.largest);
+        let _ = writeln!(out, "  \"mean_cluster\": {:.6},", self.clusters.mean);
+        let _ = writeln!(out, "  \"cluster_sizes\": [{}]",
+            list(&mut self.clusters.histogram.iter().map(|(s, n)| format!("[{s}, {n}]"))));
+        out.push('}');
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automata;
+
+    fn grid(w: usize, h: usize, f: impl Fn(usize, usize) -> u8) -> Grid {
+        let mut g = Grid::new(w, h);
+        for y in 0..h {
+            for x in 0..w {
+                g.set(x, y, f(x, y));
+            }
+        }
+        g
+    }
+
+    #[test]
+    fn entropy() {
+        assert_eq!(cell_entropy(&Grid::new(8, 8)), 0.0);
+        assert_eq!(block_entropy(&grid(8, 8, |_, _| 3), 2), 0.0);
+        // 4 états en proportions égales : 2 bits
+        assert_eq!(cell_entropy(&grid(8, 8, |x, _| (x % 4) as u8)), 2.0);
+    }
+
+    #[test]
+    fn checkerboard_correlation() {
+        let c = correlation(&grid(8, 8, |x, y| ((x + y) % 2) as u8), 3);
+        assert_eq!(c, vec![-1.0, 1.0, -1.0]);
+        assert_eq!(correlation(&Grid::new(8, 8), 2), vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn cluster_count() {
+        let mut g = Grid::new(8, 8);
+        // bloc 2×2, puis une paire de deux états différents, puis une case isolée
+        for (x, y, v) in [(1, 1, 1), (2, 1, 1), (1, 2, 1), (2, 2, 1), (5, 1, 1), (6, 1, 2), (4, 5, 1)] {
+            g.set(x, y, v);
+        }
+        let c = clusters(&g);
+        assert_eq!(c.count, 4);
+        assert_eq!(c.largest, 4);
+        assert_eq!(c.histogram, vec![(1, 3), (4, 1)]);
+        assert_eq!(c.mean, 7.0 / 4.0);
+    }
+
+    #[test]
+    fn damage_dies_out_in_a_dead_grid() {
+        let life = automata::by_name("life").expect("automate");
+        let mut g = Grid::new(16, 16);
+        let done = AtomicUsize::new(0);
+        let d = damage_spreading(life.as_ref(), &g, UpdateScheme::Synchronous, 1, (8, 8), 10, &done).expect("taille suffisante");
+        assert_eq!(d.len(), 11);
+        assert_eq!(d[0], 1.0 / 256.0);
+        assert!(d[1..].iter().all(|&h| h == 0.0));
+        assert_eq!(done.load(Ordering::Relaxed), 10);
+
+        // valeur hors des états de l'automate : pas de débordement
+        g.set(8, 8, 255);
+        let d = damage_spreading(life.as_ref(), &g, UpdateScheme::Synchronous, 1, (8, 8), 1, &done).expect("taille suffisante");
+        assert_eq!(d[0], 1.0 / 256.0);
+    }
+
+    #[test]
+    fn damage_below_min_size() {
+        let automaton = automata::by_name("wireworld").expect("automate");
+        let (w, h) = automaton.min_size();
+        let g = Grid::new(w - 1, h);
+        assert!(damage_spreading(automaton.as_ref(), &g, UpdateScheme::Synchronous, 1, (0, 0), 1, &AtomicUsize::new(0)).is_err());
+    }
+}
@@ -0,0 +1,184 @@
+use std::{
+    fmt::Write as _,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use crate::{
+    automata,
+    engine::Engine,
+    scheme::UpdateScheme,
+    seeding::{Seed, Seeding},
+};
+
+// Balayage du taux d'asynchronisme : pour chaque α, M exécutions indépendantes de T pas en
+// schéma α-asynchrone, densité finale moyenne et variance. L'exécution i part de la même
+// grille (graine seed + i) pour tous les α : seules les mises à jour diffèrent.
+
+#[derive(Clone)]
+pub struct SweepConfig {
+    // nom pour `automata::by_name` (chaque fil crée le sien)
+    pub automaton: String,
+    pub alphas: Vec<f32>,
+    pub runs: usize,
+    pub steps: u64,
+    pub width: usize,
+    pub height: usize,
+    pub seed: u64,
+    // condition initiale de chaque exécution
+    pub initial: Seed,
+    pub threads: usize,
+}
+
+impl SweepConfig {
+    pub fn new(automaton: impl Into<String>, alphas: Vec<f32>) -> Self {
+        Self {
+            automaton: automaton.into(),
+            alphas,
+            runs: 10,
+            steps: 1000,
+            width: 80,
+            height: 45,
+            seed: 0,
+            initial: Seed::Bernoulli(0.5),
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+}
+
+// Au-delà, la liste de α est refusée (un pas minuscule n'alloue rien).
+pub const MAX_ALPHAS: usize = 1000;
+
+// "0.5,0.75,0.9" ou "début:fin:pas" (fin comprise), mélangeables : "0.1,0.5:1:0.05".
+pub fn parse_alphas(s: &str) -> Result<Vec<f32>, String> {
+    let mut out = Vec::new();
+    for part in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let num = |v: &str| v.trim().parse::<f32>().map_err(|_| format!("α invalide: {v}"));
+        match part.split(':').collect::<Vec<_>>()[..] {
+            [a] => out.push(num(a)?),
+            [a, b, step] => {
+                let (a, b, step) = (num(a)?, num(b)?, num(step)?);
+                if step.is_nan() || step <= 0.0 {
+                    return Err(format!("pas invalide: {step}"));
+                }
+                if b < a {
+                    return Err(format!("intervalle vide: {part}"));
+                }
+                // compte de pas et valeurs arrondis : 0.5:1:0.05 donne 0.95 et 1 exactement
+                let n = ((b - a) / step + 1e-4).floor();
+                if n.is_nan() || n >= MAX_ALPHAS as f32 {
+                    return Err(format!("plus de {MAX_ALPHAS} valeurs de α"));
+                }
+                let n = n as usize;
+                out.extend((0..=n).map(|i| ((a + i as f32 * step) * 1e6).round() / 1e6));
+            }
+            _ => return Err(format!("intervalle invalide: {part}")),
+        }
+    }
+    if let Some(a) = out.iter().find(|a| !(0.0..=1.0).contains(*a)) {
+        return Err(format!("α hors de [0, 1]: {a}"));
+    }
+    if out.is_empty() {
+        return Err("aucune valeur de α".to_string());
+    }
+    Ok(out)
+}
+
+#[derive(Clone, Debug)]
+pub struct SweepPoint {
+    pub alpha: f32,
+    // densité finale de chaque exécution
+    pub densities: Vec<f64>,
+    pub mean: f64,
+    // variance de l'échantillon (n - 1)
+    pub variance: f64,
+}
+
+impl SweepPoint {
+    fn new(alpha: f32, densities: Vec<f64>) -> Self {
+        let n = densities.len() as f64;
+        let mean = densities.iter().sum::<f64>() / n.max(1.0);
+        let variance = densities.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / (n - 1.0).max(1.0);
+        Self { alpha, densities, mean, variance }
+    }
+
+    pub fn std_error(&self) -> f64 {
+        (self.variance / self.densities.len().max(1) as f64).sqrt()
+    }
+}
+
+// Densité finale d'une exécution.
+fn run_one(config: &SweepConfig, alpha: f32, run: usize) -> f64 {
+    let automaton = automata::by_name(&config.automaton).expect("automate vérifié par `run`");
+    let mut engine = Engine::new(config.width, config.height, UpdateScheme::Alpha(alpha), automaton);
+    let seed = config.seed.wrapping_add(run as u64);
+    engine.set_seed(seed);
+    engine.set_seeding(Some(Seeding::new(config.initial.clone(), seed)));
+    for _ in 0..config.steps {
+        engine.step_once();
+    }
+    let g = engine.current();
+    g.as_slice().iter().filter(|&&v| v != 0).count() as f64 / g.cell_count() as f64
+}
+
+// Toutes les exécutions sur `threads` fils ; `done` compte les exécutions finies.
+pub fn run(config: &SweepConfig, done: &AtomicUsize) -> Result<Vec<SweepPoint>, String> {
+    if automata::by_name(&config.automaton).is_none() {
+        return Err(format!("automate inconnu: {}", config.automaton));
+    }
+    let jobs: Vec<(usize, usize)> = (0..config.alphas.len())
+        .flat_map(|a| (0..config.runs.max(1)).map(move |r| (a, r)))
+        .collect();
+    let next = AtomicUsize::new(0);
+    let results: Vec<(usize, usize, f64)> = thread::scope(|s| {
+        let handles: Vec<_> = (0..config.threads.max(1)).map(|_| s.spawn(|| {
+            let mut out = Vec::new();
+            while let Some(&(a, r)) = jobs.get(next.fetch_add(1, Ordering::Relaxed)) {
+                out.push((a, r, run_one(config, config.alphas[a], r)));
+                done.fetch_add(1, Ordering::Relaxed);
+            }
+            out
+        })).collect();
+        handles.into_iter().flat_map(|h| h.join().expect("fil de balayage")).collect()
+    });
+
+    let mut densities = vec![vec![0.0; config.runs.max(1)]; config.alphas.len()];
+    for (a, r, d) in results {
+        densities[a][r] = d;
+    }
+    Ok(config.alphas.iter().zip(densities).map(|(&alpha, d)| SweepPoint::new(alpha, d)).collect())
+}
+
+pub fn to_csv(config: &SweepConfig, points: &[SweepPoint]) -> String {
+    let mut out = String::from("automaton,alpha,runs,steps,mean_density,variance,std_error,min,max\n");
+    for p in points {
+        let min = p.densities.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = p.densities.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let _ = writeln!(out, "{},{},{},{},{:.6},{:.8},{:.6},{:.6},{:.6}",
+            config.automaton, p.alpha, p.densities.len(), config.steps, p.mean, p.variance, p.std_error(), min, max);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alphas() {
+        assert_eq!(parse_alphas("0.5, 0.75,0.9"), Ok(vec![0.5, 0.75, 0.9]));
+        assert_eq!(parse_alphas("0:1:0.25"), Ok(vec![0.0, 0.25, 0.5, 0.75, 1.0]));
+        // fin comprise malgré l'arrondi, listes et intervalles mélangés
+        assert_eq!(parse_alphas("0.1,0.8:1:0.05"), Ok(vec![0.1, 0.8, 0.85, 0.9, 0.95, 1.0]));
+        assert_eq!(parse_alphas("0.2:0.3:0.5"), Ok(vec![0.2]));
+        assert_eq!(parse_alphas("1,").map(|a| a.len()), Ok(1));
+    }
+
+    #[test]
+    fn invalid_alphas() {
+        for bad in ["", ",", "1.5", "-0.1", "0.5:1.2:0.1", "x", "0:1", "0:1:0.1:2", "nan",
+                    "0:1:0", "0:1:-0.1", "0:1:nan", "1:0:0.1", "0:1:0.0000001"] {
+            assert!(parse_alphas(bad).is_err(), "{bad:?}");
+        }
+    }
+}
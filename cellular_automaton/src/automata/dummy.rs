@@ -1,11 +1,12 @@
 use crate::{automaton::Automaton, grid::Grid};
 
+#[derive(Clone)]
 pub struct Dummy;
 
 impl Automaton for Dummy {
-    fn name(&self) -> &'static str { "dummy" }
+    fn name(&self) -> &str { "dummy" }
 
-    fn init(&self, grid: &mut Grid) {
+    fn init(&mut self, grid: &mut Grid) {
         // petit motif: un rectangle plein
         let w = grid.width();
         let h = grid.height();
@@ -16,15 +17,10 @@ impl Automaton for Dummy {
         }
     }
 
-    fn soft_init(&self, _grid: &mut Grid) {}
+    fn soft_init(&mut self, _grid: &mut Grid) {}
 
-    fn step(&self, current: &Grid, next: &mut Grid, _async_fact: f32) {
+    fn rule(&self, current: &Grid, x: usize, y: usize) -> u8 {
         // exemple simple: inversion 0<->1
-        for y in 0..current.height() {
-            for x in 0..current.width() {
-                let v = current.get(x, y);
-                next.set(x, y, if v == 0 { 1 } else { 0 });
-            }
-        }
+        if current.get(x, y) == 0 { 1 } else { 0 }
     }
 }
@@ -0,0 +1,163 @@
+use crate::{automaton::{Automaton, StateInfo}, grid::Grid};
+use rand::{Rng, rng};
+
+// Automate 1D à rayon 1, affiché en diagramme espace-temps : la dernière ligne de la
+// grille est la génération courante, chaque pas fait remonter les lignes d'un cran
+// (la plus ancienne disparaît en haut) et calcule la nouvelle en bas. Bords en tore.
+#[derive(Clone)]
+pub struct Elementary {
+    name: String,
+    states: u8,
+    // élémentaire : indexé par 4*g + 2*c + d ; totalistique : indexé par g + c + d
+    table: Vec<u8>,
+    totalistic: bool,
+    random_init: bool,
+}
+
+impl Elementary {
+    // Règle de Wolfram 0..=255 (2 états).
+    pub fn wolfram(rule: u8) -> Self {
+        let table = (0..8).map(|i| (rule >> i) & 1).collect();
+        Self { name: format!("rule{rule}"), states: 2, table, totalistic: false, random_init: false }
+    }
+
+    // Règle totalistique à k couleurs : le chiffre n°s (base k) du code donne
+    // le nouvel état quand la somme du voisinage vaut s.
+    pub fn totalistic(k: u8, code: u128) -> Option<Self> {
+        if !(2..=8).contains(&k) {
+            return None;
+        }
+        let sums = 3 * (k as u32 - 1) + 1;
+        if code >= (k as u128).checked_pow(sums)? {
+            return None;
+        }
+
+        let mut c = code;
+        let table = (0..sums)
+            .map(|_| {
+                let d = (c % k as u128) as u8;
+                c /= k as u128;
+                d
+            })
+            .collect();
+
+        Some(Self { name: format!("totalistic{k}-{code}"), states: k, table, totalistic: true, random_init: false })
+    }
+
+    // Première ligne aléatoire au lieu d'une cellule unique au centre.
+    pub fn with_random_init(mut self) -> Self {
+        self.random_init = true;
+        self
+    }
+
+    // "rule30", "totalistic3-1599"
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        if let Some(n) = name.strip_prefix("rule") {
+            return n.parse().ok().map(Self::wolfram);
+        }
+        let (k, code) = name.strip_prefix("totalistic")?.split_once('-')?;
+        Self::totalistic(k.parse().ok()?, code.parse().ok()?)
+    }
+
+    #[inline]
+    fn cell(&self, l: u8, c: u8, r: u8) -> u8 {
+        if self.totalistic {
+            self.table[(l + c + r) as usize]
+        } else {
+            self.table[(4 * (l & 1) + 2 * (c & 1) + (r & 1)) as usize]
+        }
+    }
+}
+
+impl Automaton for Elementary {
+    fn name(&self) -> &str { &self.name }
+
+    fn states(&self) -> Vec<StateInfo> {
+        StateInfo::levels(self.states)
+    }
+
+    fn init(&mut self, grid: &mut Grid) {
+        grid.fill(0);
+
+        let w = grid.width();
+        let y = grid.height() - 1;
+        if self.random_init {
+            for x in 0..w {
+                grid.set(x, y, rng().random_range(0..self.states));
+            }
+        } else {
+            grid.set(w / 2, y, 1);
+        }
+    }
+
+    fn soft_init(&mut self, _grid: &mut Grid) {}
+
+    fn is_local(&self) -> bool { false }
+
+    fn step(&mut self, current: &Grid, next: &mut Grid) {
+        let w = current.width();
+        let h = current.height();
+
+        // défilement vers le haut
+        let row = current.as_slice();
+        next.as_mut_slice()[..w * (h - 1)].copy_from_slice(&row[w..]);
+
+        // nouvelle génération
+        let y = h - 1;
+        for x in 0..w {
+            let l = current.get(if x == 0 { w - 1 } else { x - 1 }, y).min(self.states - 1);
+            let c = current.get(x, y).min(self.states - 1);
+            let r = current.get(if x + 1 == w { 0 } else { x + 1 }, y).min(self.states - 1);
+            next.set(x, y, self.cell(l, c, r));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Diagramme espace-temps après `steps` pas, en `#` et `.`.
+    fn rows(mut a: Elementary, w: usize, steps: usize) -> Vec<String> {
+        let mut g = Grid::new(w, steps + 1);
+        a.init(&mut g);
+        let mut next = g.clone();
+        for _ in 0..steps {
+            a.step(&g, &mut next);
+            std::mem::swap(&mut g, &mut next);
+        }
+        (0..g.height())
+            .map(|y| (0..w).map(|x| if g.get(x, y) == 0 { '.' } else { '#' }).collect())
+            .collect()
+    }
+
+    #[test]
+    fn rule30() {
+        assert_eq!(rows(Elementary::wolfram(30), 11, 4), vec![
+            ".....#.....",
+            "....###....",
+            "...##..#...",
+            "..##.####..",
+            ".##..#...#.",
+        ]);
+    }
+
+    #[test]
+    fn totalistic_matches_its_elementary_twin() {
+        // code 6 (110 en base 2) : sommes 1 et 2 vivent, soit la règle 126
+        let t = Elementary::totalistic(2, 6).expect("code valide");
+        assert_eq!(rows(t, 21, 8), rows(Elementary::wolfram(126), 21, 8));
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(Elementary::parse("Rule30").map(|a| a.name), Some("rule30".to_string()));
+        assert_eq!(Elementary::parse("totalistic3-1599").map(|a| a.table.len()), Some(7));
+        // 3^7 = 2187 codes à 3 couleurs
+        assert!(Elementary::parse("totalistic3-2186").is_some());
+        for bad in ["rule256", "rule", "totalistic3-2187", "totalistic9-1", "totalistic1-0", "totalistic3", "life"] {
+            assert!(Elementary::parse(bad).is_none(), "{bad}");
+        }
+    }
+}
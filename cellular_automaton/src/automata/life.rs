@@ -1,9 +1,114 @@
-use crate::{automaton::Automaton, grid::Grid};
+use crate::{automaton::{Automaton, StateInfo}, grid::Grid};
 use rand::{Rng, rng};
 
-pub struct Life;
+// Règle "life-like" B/S : bit n de `birth` -> une cellule morte naît avec n voisins,
+// bit n de `survive` -> une cellule vivante survit avec n voisins.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rule {
+    pub birth: u16,
+    pub survive: u16,
+}
+
+impl Rule {
+    pub const CONWAY: Rule = Rule { birth: 1 << 3, survive: (1 << 2) | (1 << 3) };
+
+    // "B3/S23" (casse indifférente, l'ordre B/S peut être inversé)
+    pub fn parse(s: &str) -> Option<Self> {
+        let (a, b) = s.split_once('/')?;
+        let mut rule = Rule { birth: 0, survive: 0 };
+        let mut seen = [false; 2];
+
+        for part in [a, b] {
+            let mut chars = part.trim().chars();
+            let (mask, k) = match chars.next()?.to_ascii_uppercase() {
+                'B' => (&mut rule.birth, 0),
+                'S' => (&mut rule.survive, 1),
+                _ => return None,
+            };
+            if seen[k] { return None; }
+            seen[k] = true;
+
+            for c in chars {
+                let d = c.to_digit(10).filter(|&d| d <= 8)?;
+                *mask |= 1 << d;
+            }
+        }
+        Some(rule)
+    }
+
+    #[inline]
+    pub fn next(&self, alive: bool, n: u8) -> bool {
+        let mask = if alive { self.survive } else { self.birth };
+        mask & (1 << n) != 0
+    }
+}
+
+impl std::fmt::Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let digits = |m: u16| (0..=8).filter(|d| m & (1 << d) != 0).map(|d| d.to_string()).collect::<String>();
+        write!(f, "B{}/S{}", digits(self.birth), digits(self.survive))
+    }
+}
+
+// Soupe initiale : densité de cellules vivantes, sur toute la grille ou au centre seulement.
+#[derive(Clone, Copy, Debug)]
+pub enum Soup {
+    Full(f32),
+    Central(usize, f32),
+}
+
+#[derive(Clone)]
+pub struct Life {
+    name: String,
+    rule: Rule,
+    soup: Soup,
+    // lissage (grottes) : nombre de générations avant de figer, puis générations faites
+    iterations: Option<u32>,
+    done: u32,
+}
 
 impl Life {
+    pub fn new(name: impl Into<String>, rule: Rule, soup: Soup) -> Self {
+        Self { name: name.into(), rule, soup, iterations: None, done: 0 }
+    }
+
+    // Jeu de la vie de Conway (B3/S23).
+    pub fn conway() -> Self {
+        Self::new("life", Rule::CONWAY, Soup::Full(0.5))
+    }
+
+    // Labyrinthes : les cellules vivantes (1) sont les murs.
+    pub fn maze() -> Self {
+        Self::new("maze", Rule::parse("B3/S12345").expect("règle valide"), Soup::Central(12, 0.5))
+    }
+
+    pub fn mazectric() -> Self {
+        Self::new("mazectric", Rule::parse("B3/S1234").expect("règle valide"), Soup::Central(12, 0.5))
+    }
+
+    // Grottes : bruit à 45% lissé par B678/S345678 pendant `iterations` générations.
+    pub fn cave(iterations: u32) -> Self {
+        let mut l = Self::new("cave", Rule::parse("B678/S345678").expect("règle valide"), Soup::Full(0.45));
+        l.iterations = Some(iterations);
+        if iterations != 5 {
+            l.name = format!("cave:{iterations}");
+        }
+        l
+    }
+
+    // "B36/S23", "cave:8"
+    pub fn parse(name: &str) -> Option<Self> {
+        if let Some(n) = name.strip_prefix("cave:") {
+            return n.parse().ok().map(Self::cave);
+        }
+        let rule = Rule::parse(name)?;
+        Some(Self::new(rule.to_string(), rule, Soup::Full(0.5)))
+    }
+
+    pub fn life_rule(&self) -> Rule {
+        self.rule
+    }
+
     #[inline]
     fn count_neighbors(cur: &Grid, x: usize, y: usize) -> u8 {
         let w = cur.width();
@@ -32,66 +137,106 @@ impl Life {
 }
 
 impl Automaton for Life {
-    fn name(&self) -> &'static str { "life" }
+    fn name(&self) -> &str { &self.name }
 
-    fn init(&self, grid: &mut Grid) {
+    fn states(&self) -> Vec<StateInfo> {
+        vec![
+            StateInfo::new("mort", [0, 0, 0], ' ', "cellule morte"),
+            StateInfo::new("vivant", [255, 255, 255], '█', "cellule vivante (mur pour maze, mazectric et cave)"),
+        ]
+    }
+
+    fn init(&mut self, grid: &mut Grid) {
+        self.reset();
         grid.fill(0);
 
-        /* 
-        // Glider
-        let (x, y) = (2, 2);
-        grid.set(x + 1, y, 1);
-        grid.set(x + 2, y + 1, 1);
-        grid.set(x,     y + 2, 1);
-        grid.set(x + 1, y + 2, 1);
-        grid.set(x + 2, y + 2, 1);
-        */
-
-        for y in 0..grid.height() {
-            for x in 0..grid.width() {
-                if rng().random::<f32>() < 0.5 {
+        let w = grid.width();
+        let h = grid.height();
+        let (x0, y0, x1, y1, density) = match self.soup {
+            Soup::Full(d) => (0, 0, w, h, d),
+            Soup::Central(k, d) => {
+                let (kx, ky) = (k.min(w), k.min(h));
+                ((w - kx) / 2, (h - ky) / 2, (w + kx) / 2, (h + ky) / 2, d)
+            }
+        };
+
+        for y in y0..y1 {
+            for x in x0..x1 {
+                if rng().random::<f32>() < density {
                     grid.set(x, y, 1);
                 }
             }
         }
     }
 
-    fn soft_init(&self, _grid: &mut Grid) {}
+    fn soft_init(&mut self, _grid: &mut Grid) {}
 
-    fn step(&self, current: &Grid, next: &mut Grid, async_fact: f32) {
-        if async_fact == 0.0 {
-            let x = rng().random_range(..current.width());
-            let y = rng().random_range(..current.height());
+    fn reset(&mut self) {
+        self.done = 0;
+    }
 
-            let alive = current.get(x, y) != 0;
-            let n = Self::count_neighbors(current, x, y);
+    fn save_state(&self) -> Vec<u8> {
+        match self.iterations {
+            Some(_) => self.done.to_le_bytes().to_vec(),
+            None => Vec::new(),
+        }
+    }
 
-            let out_alive = match (alive, n) {
-                (true, 2) | (true, 3) => true,
-                (false, 3) => true,
-                _ => false,
-            };
+    fn load_state(&mut self, data: &[u8]) -> bool {
+        match (self.iterations, data) {
+            (None, []) => true,
+            (Some(_), &[a, b, c, d]) => { self.done = u32::from_le_bytes([a, b, c, d]); true }
+            _ => false,
+        }
+    }
 
-            next.set(x, y, if out_alive { 1 } else { 0 });
-        } else {
-            for y in 0..current.height() {
-                for x in 0..current.width() {
-                    if rng().random::<f32>() < async_fact {
-                        let alive = current.get(x, y) != 0;
-                        let n = Self::count_neighbors(current, x, y);
-        
-                        let out_alive = match (alive, n) {
-                            (true, 2) | (true, 3) => true,
-                            (false, 3) => true,
-                            _ => false,
-                        };
-        
-                        next.set(x, y, if out_alive { 1 } else { 0 });
-                    } else {
-                        next.set(x, y, current.get(x, y));
-                    }
-                }
+    // Vie figée (point fixe), ou lissage terminé pour les grottes.
+    fn is_finished(&self, grid: &Grid) -> bool {
+        match self.iterations {
+            Some(n) => self.done >= n,
+            None => self.is_fixed_point(grid),
+        }
+    }
+
+    fn rule(&self, current: &Grid, x: usize, y: usize) -> u8 {
+        let alive = current.get(x, y) != 0;
+        let n = Self::count_neighbors(current, x, y);
+
+        if self.rule.next(alive, n) { 1 } else { 0 }
+    }
+
+    // Le lissage des grottes compte ses générations : il est appliqué en synchrone par `step`.
+    fn is_local(&self) -> bool {
+        self.iterations.is_none()
+    }
+
+    fn step(&mut self, current: &Grid, next: &mut Grid) {
+        let frozen = self.iterations.is_some_and(|n| self.done >= n);
+        for y in 0..current.height() {
+            for x in 0..current.width() {
+                let v = if frozen { current.get(x, y) } else { self.rule(current, x, y) };
+                next.set(x, y, v);
             }
         }
+        if !frozen {
+            self.done += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rule_round_trip() {
+        for s in ["B3/S23", "B36/S23", "B0/S8"] {
+            let rule = Rule::parse(s).expect("règle valide");
+            assert_eq!(rule.to_string(), s);
+            assert_eq!(Rule::parse(&rule.to_string()), Some(rule));
+        }
+        assert_eq!(Rule::parse("s23/b3"), Some(Rule::CONWAY));
+        assert_eq!(Rule::parse("B9/S23"), None);
+        assert_eq!(Rule::parse("B3/B23"), None);
     }
 }
@@ -0,0 +1,309 @@
+use crate::{automaton::{Automaton, StateInfo}, grid::Grid};
+use rand::{Rng, rng};
+
+// Automate à blocs (voisinage de Margolus) : la grille est découpée en blocs 2x2,
+// décalés d'une case un pas sur deux. Chaque bloc est remplacé d'un coup via une table
+// de `states^4` entrées. Les cellules qui ne tombent dans aucun bloc complet (bords,
+// dimensions impaires) restent inchangées.
+//
+// Codage d'un bloc [a b / c d] : a + b*k + c*k² + d*k³ (k = nombre d'états, au plus 15
+// pour que les k⁴ codes tiennent sur un u16).
+#[derive(Clone)]
+pub struct BlockRule {
+    name: &'static str,
+    states: u8,
+    table: Vec<u16>,
+    inverse: Option<Vec<u16>>,
+    seed: fn(&mut Grid),
+    phase: usize,
+    schema: Vec<StateInfo>,
+}
+
+impl BlockRule {
+    pub const MAX_STATES: u8 = 15;
+
+    pub fn new(name: &'static str, states: u8, seed: fn(&mut Grid), f: impl Fn([u8; 4]) -> [u8; 4]) -> Self {
+        assert!((1..=Self::MAX_STATES).contains(&states), "{name}: {states} états (au plus {})", Self::MAX_STATES);
+        let k = states as usize;
+        let n = k.pow(4);
+        let table: Vec<u16> = (0..n)
+            .map(|i| Self::encode(k, f(Self::decode(k, i as u16))))
+            .collect();
+
+        // bijection -> règle réversible
+        let mut inverse = vec![None; n];
+        let mut reversible = true;
+        for (i, &o) in table.iter().enumerate() {
+            match inverse.get_mut(o as usize) {
+                Some(slot @ None) => *slot = Some(i as u16),
+                // deux blocs donnent le même, ou sortie hors des états
+                _ => {
+                    reversible = false;
+                    break;
+                }
+            }
+        }
+
+        Self {
+            name,
+            states,
+            table,
+            inverse: reversible.then(|| inverse.into_iter().map(|i| i.expect("bijection")).collect()),
+            seed,
+            phase: 0,
+            schema: StateInfo::levels(states),
+        }
+    }
+
+    // Noms et couleurs des états, à la place des niveaux de gris.
+    pub fn with_states(mut self, schema: Vec<StateInfo>) -> Self {
+        self.schema = schema;
+        self
+    }
+
+    #[inline]
+    fn encode(k: usize, b: [u8; 4]) -> u16 {
+        (b[0] as usize + k * (b[1] as usize + k * (b[2] as usize + k * b[3] as usize))) as u16
+    }
+
+    #[inline]
+    fn decode(k: usize, mut i: u16) -> [u8; 4] {
+        let mut b = [0u8; 4];
+        for v in &mut b {
+            *v = (i as usize % k) as u8;
+            i /= k as u16;
+        }
+        b
+    }
+
+    fn apply(&self, table: &[u16], off: usize, current: &Grid, next: &mut Grid) {
+        let k = self.states as usize;
+        let w = current.width();
+        let h = current.height();
+
+        next.as_mut_slice().copy_from_slice(current.as_slice());
+
+        for by in (off..h.saturating_sub(1)).step_by(2) {
+            for bx in (off..w.saturating_sub(1)).step_by(2) {
+                let cells = [(bx, by), (bx + 1, by), (bx, by + 1), (bx + 1, by + 1)];
+                let b = cells.map(|(x, y)| current.get(x, y).min(self.states - 1));
+                let out = Self::decode(k, table[Self::encode(k, b) as usize]);
+                for ((x, y), v) in cells.into_iter().zip(out) {
+                    next.set(x, y, v);
+                }
+            }
+        }
+    }
+}
+
+impl Automaton for BlockRule {
+    fn name(&self) -> &str { self.name }
+
+    fn states(&self) -> Vec<StateInfo> {
+        self.schema.clone()
+    }
+
+    // un bloc 2×2 complet
+    fn min_size(&self) -> (usize, usize) {
+        (2, 2)
+    }
+
+    fn init(&mut self, grid: &mut Grid) {
+        self.reset();
+        grid.fill(0);
+        (self.seed)(grid);
+    }
+
+    fn soft_init(&mut self, _grid: &mut Grid) {
+        self.reset();
+    }
+
+    fn reset(&mut self) {
+        self.phase = 0;
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        vec![self.phase as u8]
+    }
+
+    fn load_state(&mut self, data: &[u8]) -> bool {
+        match data {
+            [p @ (0 | 1)] => { self.phase = *p as usize; true }
+            _ => false,
+        }
+    }
+
+    fn is_local(&self) -> bool { false }
+
+    fn step(&mut self, current: &Grid, next: &mut Grid) {
+        let off = self.phase;
+        self.phase ^= 1;
+        self.apply(&self.table, off, current, next);
+    }
+
+    fn is_reversible(&self) -> bool {
+        self.inverse.is_some()
+    }
+
+    fn step_back(&mut self, current: &Grid, next: &mut Grid) {
+        if let Some(inverse) = &self.inverse {
+            self.phase ^= 1;
+            let off = self.phase;
+            self.apply(inverse, off, current, next);
+        }
+    }
+}
+
+// --- presets
+
+fn rot180(b: [u8; 4]) -> [u8; 4] {
+    [b[3], b[2], b[1], b[0]]
+}
+
+fn live(b: [u8; 4]) -> usize {
+    b.iter().filter(|&&v| v != 0).count()
+}
+
+fn complement(b: [u8; 4]) -> [u8; 4] {
+    b.map(|v| 1 - v)
+}
+
+// soupe aléatoire dans le tiers central
+fn central_soup(grid: &mut Grid, density: f32) {
+    let w = grid.width();
+    let h = grid.height();
+    for y in h / 3..(2 * h / 3) {
+        for x in w / 3..(2 * w / 3) {
+            if rng().random::<f32>() < density {
+                grid.set(x, y, 1);
+            }
+        }
+    }
+}
+
+// Billiard Ball Machine : une boule traverse le bloc en diagonale,
+// deux boules en diagonale rebondissent sur l'autre diagonale.
+pub fn billiard_ball() -> BlockRule {
+    BlockRule::new("bbm", 2, |g| central_soup(g, 0.15), |b| {
+        match b {
+            [1, 0, 0, 1] => [0, 1, 1, 0],
+            [0, 1, 1, 0] => [1, 0, 0, 1],
+            _ if live(b) == 1 => rot180(b),
+            _ => b,
+        }
+    })
+}
+
+// Critters : blocs à 2 cellules inchangés, les autres complémentés,
+// et ceux à 3 cellules tournés de 180°.
+pub fn critters() -> BlockRule {
+    BlockRule::new("critters", 2, |g| central_soup(g, 0.25), |b| {
+        match live(b) {
+            2 => b,
+            3 => rot180(complement(b)),
+            _ => complement(b),
+        }
+    })
+}
+
+// Tron : blocs vides ou pleins complémentés, les autres inchangés.
+pub fn tron() -> BlockRule {
+    BlockRule::new("tron", 2, |g| {
+        let w = g.width();
+        let h = g.height();
+        for y in h / 3..(2 * h / 3) {
+            for x in w / 3..(2 * w / 3) {
+                g.set(x, y, 1);
+            }
+        }
+    }, |b| {
+        match live(b) {
+            0 | 4 => complement(b),
+            _ => b,
+        }
+    })
+}
+
+// Sable : 0 vide, 1 sable, 2 mur. Le sable tombe, puis glisse en diagonale.
+pub fn sand() -> BlockRule {
+    BlockRule::new("sand", 3, |g| {
+        let w = g.width();
+        let h = g.height();
+        for x in 0..w {
+            g.set(x, h - 1, 2);
+        }
+        for x in w / 4..(3 * w / 4) {
+            g.set(x, 2 * h / 3, 2);
+        }
+        for y in 0..h / 3 {
+            for x in 0..w {
+                if rng().random::<f32>() < 0.4 {
+                    g.set(x, y, 1);
+                }
+            }
+        }
+    }, |[mut a, mut b, mut c, mut d]| {
+        // chute verticale
+        if a == 1 && c == 0 { a = 0; c = 1; }
+        if b == 1 && d == 0 { b = 0; d = 1; }
+
+        // glissement diagonal
+        if a == 1 && c != 0 && d == 0 { a = 0; d = 1; }
+        if b == 1 && d != 0 && c == 0 { b = 0; c = 1; }
+
+        [a, b, c, d]
+    })
+    .with_states(vec![
+        StateInfo::new("vide", [0, 0, 0], ' ', "air"),
+        StateInfo::new("sable", [230, 200, 120], '░', "grain qui tombe puis glisse en diagonale"),
+        StateInfo::new("mur", [130, 130, 140], '█', "obstacle fixe"),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    #[test]
+    fn reversible_presets_step_back() {
+        let mut r = StdRng::seed_from_u64(5);
+        // dimensions impaires : les cases hors bloc restent en place dans les deux sens
+        let mut g = Grid::new(17, 13);
+        for c in g.as_mut_slice() {
+            *c = r.random_range(0..2);
+        }
+
+        for mut rule in [billiard_ball(), critters(), tron()] {
+            assert!(rule.inverse.is_some(), "{}", rule.name);
+            assert!(rule.is_reversible());
+            for phase in [0, 1] {
+                assert!(rule.load_state(&[phase]));
+                let (mut next, mut back) = (g.clone(), g.clone());
+                rule.step(&g, &mut next);
+                assert_ne!(next.as_slice(), g.as_slice(), "{} phase {phase}", rule.name);
+                rule.step_back(&next, &mut back);
+                assert_eq!(back.as_slice(), g.as_slice(), "{} phase {phase}", rule.name);
+                assert_eq!(rule.save_state(), vec![phase]);
+            }
+        }
+    }
+
+    #[test]
+    fn sand_is_not_reversible() {
+        let mut rule = sand();
+        assert!(rule.inverse.is_none());
+        assert!(!rule.is_reversible());
+
+        let mut g = Grid::new(8, 8);
+        rule.init(&mut g);
+        let mut next = Grid::new(8, 8);
+        rule.step(&g, &mut next);
+        // pas de table inverse : `step_back` ne touche ni la grille ni la phase
+        let mut back = Grid::new(8, 8);
+        rule.step_back(&next, &mut back);
+        assert!(back.as_slice().iter().all(|&v| v == 0));
+        assert_eq!(rule.save_state(), vec![1]);
+    }
+}
@@ -1,89 +1,739 @@
-use crate::{automaton::Automaton, grid::Grid};
-use rand::{Rng, rng};
-use std::cell::{Cell, RefCell};
+use crate::{automaton::{Automaton, StateInfo}, grid::Grid};
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
 
-pub struct MazeGenerator {
-    started: Cell<bool>,
-    stack: RefCell<Vec<(usize, usize)>>,
+// Les cellules du labyrinthe sont aux coordonnées impaires de la grille : la cellule (i, j)
+// est en (2i+1, 2j+1), les murs entre deux cellules sur les cases paires. 0 = chemin, 1 = mur.
+type Cell = (usize, usize);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MazeAlgorithm {
+    Dfs,
+    Prim,
+    Kruskal,
+    Wilson,
+    AldousBroder,
+    Eller,
+    Division,
+    BinaryTree,
+    // probabilité de prendre la cellule la plus récente (1 = DFS, 0 = Prim)
+    GrowingTree(f32),
+    // DFS puis suppression des impasses avec cette probabilité (boucles)
+    Braided(f32),
 }
 
-impl MazeGenerator {
-    pub fn new() -> Self {
-        Self {
-            started: Cell::new(false),
-            stack: RefCell::new(Vec::new()),
+impl MazeAlgorithm {
+    pub const ALL: [MazeAlgorithm; 10] = [
+        MazeAlgorithm::Dfs,
+        MazeAlgorithm::Prim,
+        MazeAlgorithm::Kruskal,
+        MazeAlgorithm::Wilson,
+        MazeAlgorithm::AldousBroder,
+        MazeAlgorithm::Eller,
+        MazeAlgorithm::Division,
+        MazeAlgorithm::BinaryTree,
+        MazeAlgorithm::GrowingTree(0.5),
+        MazeAlgorithm::Braided(0.5),
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MazeAlgorithm::Dfs => "dfs",
+            MazeAlgorithm::Prim => "prim",
+            MazeAlgorithm::Kruskal => "kruskal",
+            MazeAlgorithm::Wilson => "wilson",
+            MazeAlgorithm::AldousBroder => "aldousbroder",
+            MazeAlgorithm::Eller => "eller",
+            MazeAlgorithm::Division => "division",
+            MazeAlgorithm::BinaryTree => "binarytree",
+            MazeAlgorithm::GrowingTree(_) => "growingtree",
+            MazeAlgorithm::Braided(_) => "braided",
         }
     }
 }
 
+// Avancement de l'algorithme en cours.
+#[derive(Clone, Debug, PartialEq)]
+enum Run {
+    Idle,
+    // DFS (pile), growing tree (cellules actives), Prim (frontière)
+    Stack(Vec<Cell>),
+    Active(Vec<Cell>),
+    Frontier(Vec<Cell>),
+    // Kruskal : arêtes restantes (mélangées) et union-find sur les cellules
+    Edges(Vec<(Cell, Cell)>, Vec<usize>),
+    // Wilson : cellules de départ restantes
+    Remaining(Vec<Cell>),
+    // Aldous-Broder : position et nombre de cellules non visitées
+    Walk(Cell, usize),
+    // Eller : ligne courante, ensemble de chaque colonne, prochain identifiant libre
+    Eller(usize, Vec<usize>, usize),
+    // division récursive : chambres (x0, y0, x1, y1) en cellules, bornes exclues
+    Chambers(Vec<(usize, usize, usize, usize)>),
+    // arbre binaire, tressage : prochaine cellule en ordre ligne par ligne
+    Raster(usize),
+    Braid(usize),
+    Done,
+}
 
-impl Automaton for MazeGenerator {
-    fn name(&self) -> &'static str {"generator"}
+impl Run {
+    // Cellules, ensembles et chambres dans une grille de cols×rows cellules.
+    fn fits(&self, cols: usize, rows: usize) -> bool {
+        let n = cols * rows;
+        let cell = |&(i, j): &Cell| i < cols && j < rows;
+        match self {
+            Run::Idle | Run::Done | Run::Raster(_) | Run::Braid(_) => true,
+            Run::Stack(v) | Run::Active(v) | Run::Frontier(v) | Run::Remaining(v) => v.iter().all(cell),
+            Run::Edges(edges, sets) => {
+                sets.len() == n && sets.iter().all(|&s| s < n) && edges.iter().all(|(a, b)| cell(a) && cell(b))
+            }
+            Run::Walk(at, left) => cell(at) && *left < n,
+            Run::Eller(row, sets, _) => *row < rows && sets.len() == cols,
+            Run::Chambers(v) => v.iter().all(|&(x0, y0, x1, y1)| x0 <= x1 && x1 <= cols && y0 <= y1 && y1 <= rows),
+        }
+    }
+}
 
-    fn init(&self, grid: &mut Grid){
-        grid.fill(1);
+#[derive(Clone)]
+pub struct MazeGenerator {
+    name: String,
+    algorithm: MazeAlgorithm,
+    run: Run,
+    // graine fixée : chaque `reset` refait le même labyrinthe (sinon tirages libres)
+    seed: Option<u64>,
+    rng: StdRng,
+}
+
+impl Default for MazeGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MazeGenerator {
+    pub fn new() -> Self {
+        Self::with_algorithm(MazeAlgorithm::Dfs)
     }
 
-    fn soft_init(&self, _grid: &mut Grid) {}
+    pub fn with_algorithm(algorithm: MazeAlgorithm) -> Self {
+        let name = match algorithm {
+            MazeAlgorithm::Dfs => "generator".to_string(),
+            a => format!("generator-{}", a.name()),
+        };
+        Self { name, algorithm, run: Run::Idle, seed: None, rng: StdRng::from_os_rng() }
+    }
 
-    fn step(&self, current: &Grid, next: &mut Grid, _async_fact: f32){
-        for y in 0..current.height() {
-            for x in 0..current.width() {
-                next.set(x, y, current.get(x, y));
-            }
+    // "generator", "generator-prim", "generator-growingtree:0.75", "generator-braided:0.3"
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        if name == "generator" {
+            return Some(Self::new());
         }
+        let spec = name.strip_prefix("generator-")?;
+        let (algo, param) = match spec.split_once(':') {
+            Some((a, p)) => (a, Some(p.parse::<f32>().ok().filter(|p| (0.0..=1.0).contains(p))?)),
+            None => (spec, None),
+        };
 
-        // --- init DFS (une seule fois)
-        if !self.started.get() {
-            self.started.set(true);
-
-            let sx = 1;
-            let sy = 1;
+        let algorithm = MazeAlgorithm::ALL.into_iter().find(|a| a.name() == algo)?;
+        let algorithm = match (algorithm, param) {
+            (MazeAlgorithm::GrowingTree(_), Some(p)) => MazeAlgorithm::GrowingTree(p),
+            (MazeAlgorithm::Braided(_), Some(p)) => MazeAlgorithm::Braided(p),
+            (a, None) => a,
+            _ => return None,
+        };
 
-            next.set(sx, sy, 0);
-            self.stack.borrow_mut().push((sx, sy));
-            return;
+        let mut g = Self::with_algorithm(algorithm);
+        if param.is_some() {
+            g.name = format!("generator-{spec}");
         }
+        Some(g)
+    }
 
-        // --- DFS : un pas par step
-        let mut rng = rng();
-        let mut stack = self.stack.borrow_mut();
+    // Labyrinthe reproductible : mêmes tirages depuis chaque `init`.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
 
-        if let Some(&(x, y)) = stack.last() {
-            let w = next.width();
-            let h = next.height();
+    pub fn algorithm(&self) -> MazeAlgorithm {
+        self.algorithm
+    }
 
-            // voisins à distance 2 (cellules impaires)
-            let mut neigh = Vec::new();
-            let dirs = [(-2i32, 0i32), (2, 0), (0, -2), (0, 2)];
+    // Un pas de l'algorithme sur `g` (creuse au plus un passage, ou une branche pour Wilson).
+    fn advance(&mut self, g: &mut Grid, rng: &mut StdRng) {
+        let (cols, rows) = dims(g);
+        // état chargé (`load_state`) incohérent avec cette grille : arrêt plutôt qu'un accès hors grille
+        if cols == 0 || rows == 0 || !self.run.fits(cols, rows) {
+            self.run = Run::Done;
+            return;
+        }
 
-            for (dx, dy) in dirs {
-                let nx = x as i32 + dx;
-                let ny = y as i32 + dy;
+        let run = std::mem::replace(&mut self.run, Run::Done);
+        self.run = match run {
+            Run::Idle => self.start(g, rng),
+            Run::Stack(stack) => self.dfs(g, rng, stack),
+            Run::Active(active) => {
+                let bias = match self.algorithm { MazeAlgorithm::GrowingTree(b) => b, _ => 1.0 };
+                growing_tree(g, rng, active, bias)
+            }
+            Run::Frontier(frontier) => prim(g, rng, frontier),
+            Run::Edges(edges, sets) => kruskal(g, edges, sets),
+            Run::Remaining(remaining) => wilson(g, rng, remaining),
+            Run::Walk(at, left) => aldous_broder(g, rng, at, left),
+            Run::Eller(row, sets, next_id) => eller(g, rng, row, sets, next_id),
+            Run::Chambers(chambers) => division(g, rng, chambers),
+            Run::Raster(i) => binary_tree(g, rng, i),
+            Run::Braid(i) => {
+                let p = match self.algorithm { MazeAlgorithm::Braided(p) => p, _ => 0.0 };
+                braid(g, rng, i, p)
+            }
+            Run::Done => Run::Done,
+        };
+    }
 
-                if nx > 0 && ny > 0 && (nx as usize) < w - 1 && (ny as usize) < h - 1 {
-                    let nx = nx as usize;
-                    let ny = ny as usize;
+    fn start(&self, g: &mut Grid, rng: &mut StdRng) -> Run {
+        let (cols, rows) = dims(g);
+        let random_cell = |rng: &mut StdRng| (rng.random_range(0..cols), rng.random_range(0..rows));
 
-                    if next.get(nx, ny) == 1 {
-                        neigh.push((nx, ny));
+        match self.algorithm {
+            MazeAlgorithm::Dfs | MazeAlgorithm::Braided(_) => {
+                // départ fixe en (1,1)
+                carve(g, (0, 0));
+                Run::Stack(vec![(0, 0)])
+            }
+            MazeAlgorithm::GrowingTree(_) => {
+                let c = random_cell(rng);
+                carve(g, c);
+                Run::Active(vec![c])
+            }
+            MazeAlgorithm::Prim => {
+                let c = random_cell(rng);
+                carve(g, c);
+                Run::Frontier(neighbors(c, cols, rows).collect())
+            }
+            MazeAlgorithm::Kruskal => {
+                let mut edges = Vec::new();
+                for j in 0..rows {
+                    for i in 0..cols {
+                        if i + 1 < cols { edges.push(((i, j), (i + 1, j))); }
+                        if j + 1 < rows { edges.push(((i, j), (i, j + 1))); }
+                    }
+                }
+                edges.shuffle(rng);
+                Run::Edges(edges, (0..cols * rows).collect())
+            }
+            MazeAlgorithm::Wilson => {
+                let mut cells: Vec<Cell> = (0..rows).flat_map(|j| (0..cols).map(move |i| (i, j))).collect();
+                cells.shuffle(rng);
+                if let Some(root) = cells.pop() {
+                    carve(g, root);
+                }
+                Run::Remaining(cells)
+            }
+            MazeAlgorithm::AldousBroder => {
+                let c = random_cell(rng);
+                carve(g, c);
+                Run::Walk(c, cols * rows - 1)
+            }
+            MazeAlgorithm::Eller => Run::Eller(0, (0..cols).collect(), cols),
+            MazeAlgorithm::Division => {
+                // on part d'une grande salle vide entourée de murs
+                for y in 1..2 * rows {
+                    for x in 1..2 * cols {
+                        g.set(x, y, 0);
                     }
                 }
+                Run::Chambers(vec![(0, 0, cols, rows)])
             }
+            MazeAlgorithm::BinaryTree => Run::Raster(0),
+        }
+    }
+
+    // --- DFS : un pas par step
+    fn dfs(&self, g: &mut Grid, rng: &mut StdRng, mut stack: Vec<Cell>) -> Run {
+        let (cols, rows) = dims(g);
+
+        if let Some(&c) = stack.last() {
+            let neigh: Vec<Cell> = neighbors(c, cols, rows).filter(|&n| !is_open(g, n)).collect();
 
             if neigh.is_empty() {
                 // backtrack
                 stack.pop();
             } else {
                 // creuse vers un voisin aléatoire
-                let (nx, ny) = neigh[rng.random_range(0..neigh.len())];
-                let wx = (x + nx) / 2;
-                let wy = (y + ny) / 2;
+                let n = neigh[rng.random_range(0..neigh.len())];
+                carve_between(g, c, n); // casse le mur
+                carve(g, n); // nouvelle cellule
+                stack.push(n);
+            }
+        }
 
-                next.set(wx, wy, 0); // casse le mur
-                next.set(nx, ny, 0); // nouvelle cellule
-                stack.push((nx, ny));
+        match (stack.is_empty(), self.algorithm) {
+            (false, _) => Run::Stack(stack),
+            (true, MazeAlgorithm::Braided(_)) => Run::Braid(0),
+            (true, _) => Run::Done,
+        }
+    }
+
+    // (tag, mots u32) pour `save_state`
+    fn encode(&self) -> (u8, Vec<u32>) {
+        let cells = |v: &[Cell]| v.iter().flat_map(|&(i, j)| [i as u32, j as u32]).collect::<Vec<_>>();
+        match &self.run {
+            Run::Idle => (0, vec![]),
+            Run::Stack(v) => (1, cells(v)),
+            Run::Active(v) => (2, cells(v)),
+            Run::Frontier(v) => (3, cells(v)),
+            Run::Edges(edges, sets) => {
+                let mut out = vec![edges.len() as u32];
+                for &(a, b) in edges {
+                    out.extend([a.0, a.1, b.0, b.1].map(|v| v as u32));
+                }
+                out.extend(sets.iter().map(|&s| s as u32));
+                (4, out)
+            }
+            Run::Remaining(v) => (5, cells(v)),
+            Run::Walk(at, left) => (6, vec![at.0 as u32, at.1 as u32, *left as u32]),
+            Run::Eller(row, sets, next_id) => {
+                let mut out = vec![*row as u32, *next_id as u32];
+                out.extend(sets.iter().map(|&s| s as u32));
+                (7, out)
+            }
+            Run::Chambers(v) => (8, v.iter().flat_map(|&(a, b, c, d)| [a, b, c, d].map(|x| x as u32)).collect()),
+            Run::Raster(i) => (9, vec![*i as u32]),
+            Run::Braid(i) => (10, vec![*i as u32]),
+            Run::Done => (11, vec![]),
+        }
+    }
+
+    fn decode(tag: u8, w: &[usize]) -> Option<Run> {
+        let cells = |w: &[usize]| w.len().is_multiple_of(2);
+        let pairs = |w: &[usize]| w.chunks_exact(2).map(|c| (c[0], c[1])).collect::<Vec<_>>();
+        Some(match tag {
+            0 if w.is_empty() => Run::Idle,
+            1 if cells(w) => Run::Stack(pairs(w)),
+            2 if cells(w) => Run::Active(pairs(w)),
+            3 if cells(w) => Run::Frontier(pairs(w)),
+            4 => {
+                let (&n, rest) = w.split_first()?;
+                let edges = rest.get(..4 * n)?.chunks_exact(4).map(|c| ((c[0], c[1]), (c[2], c[3]))).collect();
+                Run::Edges(edges, rest[4 * n..].to_vec())
             }
+            5 if cells(w) => Run::Remaining(pairs(w)),
+            6 if w.len() == 3 => Run::Walk((w[0], w[1]), w[2]),
+            7 if w.len() >= 2 => Run::Eller(w[0], w[2..].to_vec(), w[1]),
+            8 if w.len().is_multiple_of(4) => Run::Chambers(w.chunks_exact(4).map(|c| (c[0], c[1], c[2], c[3])).collect()),
+            9 if w.len() == 1 => Run::Raster(w[0]),
+            10 if w.len() == 1 => Run::Braid(w[0]),
+            11 if w.is_empty() => Run::Done,
+            _ => return None,
+        })
+    }
+}
+
+impl Automaton for MazeGenerator {
+    fn name(&self) -> &str { &self.name }
+
+    fn states(&self) -> Vec<StateInfo> {
+        vec![
+            StateInfo::new("passage", [0, 0, 0], ' ', "case creusée"),
+            StateInfo::new("mur", [255, 255, 255], '█', "mur pas encore creusé"),
+        ]
+    }
+
+    // au moins une cellule entourée de murs
+    fn min_size(&self) -> (usize, usize) {
+        (3, 3)
+    }
+
+    fn init(&mut self, grid: &mut Grid){
+        self.reset();
+        grid.fill(1);
+    }
+
+    fn reset(&mut self) {
+        self.run = Run::Idle;
+        if let Some(seed) = self.seed {
+            self.rng = StdRng::seed_from_u64(seed);
+        }
+    }
+
+    // tag (u8), puis l'état de l'algorithme en u32 LE
+    fn save_state(&self) -> Vec<u8> {
+        let (tag, words) = self.encode();
+        let mut out = vec![tag];
+        for v in words {
+            out.extend_from_slice(&v.to_le_bytes());
         }
+        out
     }
-}
\ No newline at end of file
+
+    fn load_state(&mut self, data: &[u8]) -> bool {
+        let Some((&tag, rest)) = data.split_first() else { return false };
+        if !rest.len().is_multiple_of(4) { return false; }
+
+        let words: Vec<usize> = rest
+            .chunks_exact(4)
+            .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]) as usize)
+            .collect();
+        match Self::decode(tag, &words) {
+            Some(run) => { self.run = run; true }
+            None => false,
+        }
+    }
+
+    fn soft_init(&mut self, _grid: &mut Grid) {}
+
+    fn is_finished(&self, _grid: &Grid) -> bool {
+        self.run == Run::Done
+    }
+
+    fn is_local(&self) -> bool { false }
+
+    fn step(&mut self, current: &Grid, next: &mut Grid){
+        next.as_mut_slice().copy_from_slice(current.as_slice());
+        // `advance` emprunte aussi `self` : tirages sur une copie, reprise ensuite
+        let mut rng = self.rng.clone();
+        self.advance(next, &mut rng);
+        self.rng = rng;
+    }
+}
+
+// --- outils sur la grille des cellules
+
+fn dims(g: &Grid) -> (usize, usize) {
+    ((g.width().saturating_sub(1)) / 2, (g.height().saturating_sub(1)) / 2)
+}
+
+fn at(c: Cell) -> (usize, usize) {
+    (2 * c.0 + 1, 2 * c.1 + 1)
+}
+
+fn is_open(g: &Grid, c: Cell) -> bool {
+    let (x, y) = at(c);
+    g.get(x, y) == 0
+}
+
+fn carve(g: &mut Grid, c: Cell) {
+    let (x, y) = at(c);
+    g.set(x, y, 0);
+}
+
+fn carve_between(g: &mut Grid, a: Cell, b: Cell) {
+    g.set(a.0 + b.0 + 1, a.1 + b.1 + 1, 0);
+}
+
+fn is_passage(g: &Grid, a: Cell, b: Cell) -> bool {
+    g.get(a.0 + b.0 + 1, a.1 + b.1 + 1) == 0
+}
+
+fn neighbors(c: Cell, cols: usize, rows: usize) -> impl Iterator<Item = Cell> {
+    let (i, j) = c;
+    [
+        (i > 0).then(|| (i - 1, j)),
+        (i + 1 < cols).then_some((i + 1, j)),
+        (j > 0).then(|| (i, j - 1)),
+        (j + 1 < rows).then_some((i, j + 1)),
+    ]
+    .into_iter()
+    .flatten()
+}
+
+fn pick<T: Copy>(rng: &mut StdRng, v: &[T]) -> Option<T> {
+    (!v.is_empty()).then(|| v[rng.random_range(0..v.len())])
+}
+
+// --- algorithmes (un pas chacun)
+
+fn growing_tree(g: &mut Grid, rng: &mut StdRng, mut active: Vec<Cell>, bias: f32) -> Run {
+    let (cols, rows) = dims(g);
+    if active.is_empty() {
+        return Run::Done;
+    }
+
+    // la plus récente (comme DFS) ou une au hasard (comme Prim)
+    let k = if rng.random::<f32>() < bias { active.len() - 1 } else { rng.random_range(0..active.len()) };
+    let c = active[k];
+    let closed: Vec<Cell> = neighbors(c, cols, rows).filter(|&n| !is_open(g, n)).collect();
+
+    match pick(rng, &closed) {
+        Some(n) => {
+            carve_between(g, c, n);
+            carve(g, n);
+            active.push(n);
+        }
+        None => { active.remove(k); }
+    }
+    Run::Active(active)
+}
+
+fn prim(g: &mut Grid, rng: &mut StdRng, mut frontier: Vec<Cell>) -> Run {
+    let (cols, rows) = dims(g);
+    if frontier.is_empty() {
+        return Run::Done;
+    }
+
+    let c = frontier.swap_remove(rng.random_range(0..frontier.len()));
+    let inside: Vec<Cell> = neighbors(c, cols, rows).filter(|&n| is_open(g, n)).collect();
+    if let Some(n) = pick(rng, &inside) {
+        carve_between(g, c, n);
+    }
+    carve(g, c);
+
+    for n in neighbors(c, cols, rows) {
+        if !is_open(g, n) && !frontier.contains(&n) {
+            frontier.push(n);
+        }
+    }
+    Run::Frontier(frontier)
+}
+
+fn kruskal(g: &mut Grid, mut edges: Vec<(Cell, Cell)>, mut sets: Vec<usize>) -> Run {
+    let (cols, _) = dims(g);
+
+    fn find(sets: &mut [usize], mut a: usize) -> usize {
+        while sets[a] != a {
+            sets[a] = sets[sets[a]];
+            a = sets[a];
+        }
+        a
+    }
+
+    // saute les arêtes internes à un même ensemble jusqu'à une fusion
+    while let Some((a, b)) = edges.pop() {
+        let ra = find(&mut sets, a.1 * cols + a.0);
+        let rb = find(&mut sets, b.1 * cols + b.0);
+        if ra != rb {
+            sets[rb] = ra;
+            carve(g, a);
+            carve(g, b);
+            carve_between(g, a, b);
+            return Run::Edges(edges, sets);
+        }
+    }
+    Run::Done
+}
+
+fn wilson(g: &mut Grid, rng: &mut StdRng, mut remaining: Vec<Cell>) -> Run {
+    let (cols, rows) = dims(g);
+
+    let Some(start) = std::iter::from_fn(|| remaining.pop()).find(|&c| !is_open(g, c)) else {
+        return Run::Done;
+    };
+
+    // marche aléatoire à boucles effacées jusqu'à toucher le labyrinthe
+    let mut pos = vec![usize::MAX; cols * rows];
+    let mut path = vec![start];
+    pos[start.1 * cols + start.0] = 0;
+
+    loop {
+        let c = *path.last().expect("chemin non vide");
+        let neigh: Vec<Cell> = neighbors(c, cols, rows).collect();
+        let n = pick(rng, &neigh).expect("au moins un voisin");
+
+        if is_open(g, n) {
+            for w in path.windows(2) {
+                carve_between(g, w[0], w[1]);
+            }
+            for &p in &path {
+                carve(g, p);
+            }
+            carve_between(g, c, n);
+            break;
+        }
+
+        let k = pos[n.1 * cols + n.0];
+        if k != usize::MAX {
+            for p in path.drain(k + 1..) {
+                pos[p.1 * cols + p.0] = usize::MAX;
+            }
+        } else {
+            pos[n.1 * cols + n.0] = path.len();
+            path.push(n);
+        }
+    }
+
+    Run::Remaining(remaining)
+}
+
+fn aldous_broder(g: &mut Grid, rng: &mut StdRng, mut at: Cell, mut left: usize) -> Run {
+    let (cols, rows) = dims(g);
+
+    // marche jusqu'à la prochaine cellule jamais visitée
+    while left > 0 {
+        let neigh: Vec<Cell> = neighbors(at, cols, rows).collect();
+        let Some(n) = pick(rng, &neigh) else { return Run::Done };
+        if !is_open(g, n) {
+            carve_between(g, at, n);
+            carve(g, n);
+            left -= 1;
+            return Run::Walk(n, left);
+        }
+        at = n;
+    }
+    Run::Done
+}
+
+fn eller(g: &mut Grid, rng: &mut StdRng, row: usize, mut sets: Vec<usize>, mut next_id: usize) -> Run {
+    let (cols, rows) = dims(g);
+    let last = row + 1 == rows;
+
+    for i in 0..cols {
+        carve(g, (i, row));
+    }
+
+    // fusions horizontales (toutes sur la dernière ligne)
+    for i in 0..cols.saturating_sub(1) {
+        if sets[i] != sets[i + 1] && (last || rng.random::<bool>()) {
+            carve_between(g, (i, row), (i + 1, row));
+            let (keep, drop) = (sets[i], sets[i + 1]);
+            for s in &mut sets {
+                if *s == drop { *s = keep; }
+            }
+        }
+    }
+
+    if last {
+        return Run::Done;
+    }
+
+    // au moins une descente par ensemble
+    let mut down = vec![false; cols];
+    let mut ids: Vec<usize> = sets.clone();
+    ids.sort_unstable();
+    ids.dedup();
+    for id in ids {
+        let members: Vec<usize> = (0..cols).filter(|&i| sets[i] == id).collect();
+        for &i in &members {
+            down[i] = rng.random::<bool>();
+        }
+        if !members.iter().any(|&i| down[i]) {
+            down[pick(rng, &members).expect("ensemble non vide")] = true;
+        }
+    }
+
+    for i in 0..cols {
+        if down[i] {
+            carve_between(g, (i, row), (i, row + 1));
+        } else {
+            sets[i] = next_id;
+            next_id += 1;
+        }
+    }
+    Run::Eller(row + 1, sets, next_id)
+}
+
+fn division(g: &mut Grid, rng: &mut StdRng, mut chambers: Vec<(usize, usize, usize, usize)>) -> Run {
+    while let Some((x0, y0, x1, y1)) = chambers.pop() {
+        let (cw, ch) = (x1 - x0, y1 - y0);
+        if cw < 2 && ch < 2 {
+            continue;
+        }
+
+        // coupe le côté le plus long, au hasard si égalité
+        let vertical = ch < 2 || (cw >= 2 && (cw > ch || (cw == ch && rng.random::<bool>())));
+        if vertical {
+            let k = rng.random_range(x0 + 1..x1);
+            let door = rng.random_range(y0..y1);
+            for y in 2 * y0 + 1..2 * y1 {
+                if y != 2 * door + 1 { g.set(2 * k, y, 1); }
+            }
+            chambers.push((x0, y0, k, y1));
+            chambers.push((k, y0, x1, y1));
+        } else {
+            let k = rng.random_range(y0 + 1..y1);
+            let door = rng.random_range(x0..x1);
+            for x in 2 * x0 + 1..2 * x1 {
+                if x != 2 * door + 1 { g.set(x, 2 * k, 1); }
+            }
+            chambers.push((x0, y0, x1, k));
+            chambers.push((x0, k, x1, y1));
+        }
+        return Run::Chambers(chambers);
+    }
+    Run::Done
+}
+
+fn binary_tree(g: &mut Grid, rng: &mut StdRng, i: usize) -> Run {
+    let (cols, rows) = dims(g);
+    if i >= cols * rows {
+        return Run::Done;
+    }
+
+    // chaque cellule s'ouvre vers le nord ou l'ouest
+    let c = (i % cols, i / cols);
+    carve(g, c);
+    let options: Vec<Cell> = [
+        (c.1 > 0).then(|| (c.0, c.1 - 1)),
+        (c.0 > 0).then(|| (c.0 - 1, c.1)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if let Some(n) = pick(rng, &options) {
+        carve_between(g, c, n);
+    }
+    Run::Raster(i + 1)
+}
+
+fn braid(g: &mut Grid, rng: &mut StdRng, mut i: usize, p: f32) -> Run {
+    let (cols, rows) = dims(g);
+
+    // prochaine impasse (une seule ouverture)
+    while i < cols * rows {
+        let c = (i % cols, i / cols);
+        i += 1;
+
+        let neigh: Vec<Cell> = neighbors(c, cols, rows).collect();
+        if neigh.iter().filter(|&&n| is_passage(g, c, n)).count() != 1 {
+            continue;
+        }
+
+        if rng.random::<f32>() < p {
+            // de préférence vers une autre impasse
+            let walls: Vec<Cell> = neigh.into_iter().filter(|&n| !is_passage(g, c, n)).collect();
+            let dead: Vec<Cell> = walls
+                .iter()
+                .copied()
+                .filter(|&n| neighbors(n, cols, rows).filter(|&m| is_passage(g, n, m)).count() == 1)
+                .collect();
+            if let Some(n) = pick(rng, &dead).or_else(|| pick(rng, &walls)) {
+                carve_between(g, c, n);
+            }
+        }
+        return Run::Braid(i);
+    }
+    Run::Done
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_state_round_trip() {
+        for algorithm in MazeAlgorithm::ALL {
+            let mut a = MazeGenerator::with_algorithm(algorithm).with_seed(5);
+            let (mut g, mut next) = (Grid::new(15, 11), Grid::new(15, 11));
+            a.init(&mut g);
+            for _ in 0..10 {
+                a.step(&g, &mut next);
+                g.swap(&mut next);
+            }
+            let mut b = MazeGenerator::with_algorithm(algorithm);
+            assert!(b.load_state(&a.save_state()), "{}", algorithm.name());
+            assert!(b.run == a.run && a.run.fits(7, 5), "{}", algorithm.name());
+        }
+    }
+
+    #[test]
+    fn state_outside_the_grid_stops() {
+        let mut a = MazeGenerator::new();
+        // pile avec une cellule (100, 100) : hors d'une grille 15×11 (7×5 cellules)
+        let state: Vec<u8> = [1u8].into_iter().chain([0u32, 0, 100, 100].iter().flat_map(|v| v.to_le_bytes())).collect();
+        assert!(a.load_state(&state));
+        let (g, mut next) = (Grid::new(15, 11), Grid::new(15, 11));
+        a.step(&g, &mut next);
+        assert!(a.is_finished(&next));
+    }
+}
@@ -1,20 +1,91 @@
-use crate::{automaton::Automaton, grid::Grid, automata::patterns::ALL_PATTERNS};
-use rand::{Rng, rng};
-
-pub struct MazeSolver;
-
-/*
-0 -> chemin
-1 -> murs
-2 -> départ
-3 -> arrivée
-4 -> exploration
-5 -> parcouru
-6 -> suppression
-7 -> trajet
-*/
+use std::{path::Path, sync::Arc};
+
+use crate::{automaton::{Automaton, StateInfo}, grid::Grid, automata::patterns::{PatternError, PatternSet}};
+
+// Motifs de suppression par défaut, voir le format dans le fichier.
+const DEFAULT_PATTERNS: &str = include_str!("mazesolver.pat");
+
+#[derive(Clone)]
+pub struct MazeSolver {
+    name: String,
+    patterns: Arc<PatternSet>,
+}
+
+impl Default for MazeSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl MazeSolver {
+    pub fn new() -> Self {
+        let patterns = PatternSet::parse(DEFAULT_PATTERNS).expect("motifs par défaut valides");
+        Self { name: "mazesolver".to_string(), patterns: Arc::new(patterns) }
+    }
+
+    // Motifs lus depuis un fichier (relu à chaque appel, pour itérer sans recompiler).
+    pub fn from_file(path: &str) -> Result<Self, PatternError> {
+        let patterns = PatternSet::load(Path::new(path))?;
+        Ok(Self { name: format!("mazesolver:{path}"), patterns: Arc::new(patterns) })
+    }
+
+    // "mazesolver" ou "mazesolver:<fichier de motifs>"
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.split_once(':') {
+            Some((head, path)) if head.eq_ignore_ascii_case("mazesolver") => Self::from_file(path.trim()).ok(),
+            None if name.eq_ignore_ascii_case("mazesolver") => Some(Self::new()),
+            _ => None,
+        }
+    }
+
+    pub fn patterns(&self) -> &PatternSet {
+        &self.patterns
+    }
+
+    // Départ sur la case libre la plus proche du coin haut-gauche, arrivée sur la case
+    // accessible (4-voisinage, tore) la plus éloignée du départ. Efface les anciens 2/3.
+    pub fn place_endpoints(grid: &mut Grid) {
+        let w = grid.width();
+        let h = grid.height();
+
+        for v in grid.as_mut_slice() {
+            if *v == 2 || *v == 3 { *v = 0; }
+        }
+
+        let Some(start) = (0..w * h)
+            .filter(|&i| grid.as_slice()[i] == 0)
+            .min_by_key(|&i| (i % w + i / w, i / w))
+        else {
+            return;
+        };
+
+        // BFS : la dernière case atteinte est la plus éloignée
+        let mut seen = vec![false; w * h];
+        let mut queue = std::collections::VecDeque::from([start]);
+        let mut goal = start;
+        seen[start] = true;
+        while let Some(i) = queue.pop_front() {
+            goal = i;
+            let (x, y) = (i % w, i / w);
+            let xm1 = if x == 0 { w - 1 } else { x - 1 };
+            let xp1 = if x + 1 == w { 0 } else { x + 1 };
+            let ym1 = if y == 0 { h - 1 } else { y - 1 };
+            let yp1 = if y + 1 == h { 0 } else { y + 1 };
+            for (nx, ny) in [(x, ym1), (xm1, y), (xp1, y), (x, yp1)] {
+                let j = ny * w + nx;
+                if !seen[j] && grid.get(nx, ny) == 0 {
+                    seen[j] = true;
+                    queue.push_back(j);
+                }
+            }
+        }
+
+        grid.set(start % w, start / w, 2);
+        if goal != start {
+            grid.set(goal % w, goal / w, 3);
+        }
+    }
+
     #[inline]
     fn get_4neigh_count(cur: &Grid, x: usize, y: usize) -> [u8; 8] {
         let w = cur.width();
@@ -57,68 +128,30 @@ impl MazeSolver {
                 
         false
     }
-
-    #[inline]
-    fn match_pattern(cur: &Grid, x: usize, y: usize, pat: &[[i8; 5]; 5]) -> bool {
-        let w = cur.width() as i32;
-        let h = cur.height() as i32;
-        let cx = x as i32;
-        let cy = y as i32;
-
-        #[inline]
-        fn wrap(a: i32, m: i32) -> usize {
-            a.rem_euclid(m) as usize
-        }
-
-        // 8 symétries du carré (D4) sur les indices (i,j) du pattern 5x5
-        // On parcourt la fenêtre 5x5 dans la grille, et on compare à pat[pj][pi].
-        for t in 0..8u8 {
-            let mut ok = true;
-
-            for j in 0..5usize {
-                for i in 0..5usize {
-                    // Coord grille de la case (i,j) de la fenêtre (wrap)
-                    let gx = wrap(cx + (i as i32 - 2), w);
-                    let gy = wrap(cy + (j as i32 - 2), h);
-
-                    // Indices dans le pattern après transformation
-                    let (pi, pj) = match t {
-                        0 => (i, j),               // identité
-                        1 => (4 - j, i),           // rot 90
-                        2 => (4 - i, 4 - j),       // rot 180
-                        3 => (j, 4 - i),           // rot 270
-                        4 => (4 - i, j),           // miroir vertical
-                        5 => (4 - j, 4 - i),       // miroir vertical + rot 90
-                        6 => (i, 4 - j),           // miroir vertical + rot 180 (miroir horizontal)
-                        _ => (j, i),               // miroir vertical + rot 270 (miroir diag)
-                    };
-
-                    let want = pat[pj][pi];
-                    if want == -1 {
-                        continue; // wildcard
-                    }
-
-                    if cur.get(gx, gy) != want as u8 {
-                        ok = false;
-                        break;
-                    }
-                }
-                if !ok { break; }
-            }
-
-            if ok {
-                return true;
-            }
-        }
-
-        false
-    }
 }
 
 impl Automaton for MazeSolver{
-    fn name(&self) -> &'static str { "mazesolver" }
+    fn name(&self) -> &str { &self.name }
+
+    fn states(&self) -> Vec<StateInfo> {
+        vec![
+            StateInfo::new("chemin", [0, 0, 0], ' ', "case libre"),
+            StateInfo::new("mur", [255, 255, 255], '█', "case infranchissable"),
+            StateInfo::new("départ", [0, 0, 139], 'S', "origine de l'exploration"),
+            StateInfo::new("arrivée", [128, 0, 128], 'G', "case à atteindre"),
+            StateInfo::new("exploration", [0, 255, 0], '+', "front de l'exploration en largeur").fixed(),
+            StateInfo::new("parcouru", [0, 100, 0], '.', "case déjà explorée").fixed(),
+            StateInfo::new("suppression", [255, 0, 0], 'x', "case de trajet retirée (impasse ou raccourci)").fixed(),
+            StateInfo::new("trajet", [255, 215, 0], '*', "chemin de l'arrivée vers le départ").fixed(),
+        ]
+    }
+
+    // bordure de murs et au moins une case libre
+    fn min_size(&self) -> (usize, usize) {
+        (3, 3)
+    }
 
-    fn init(&self, grid: &mut Grid) {
+    fn init(&mut self, grid: &mut Grid) {
         let w = grid.width();
         let h = grid.height();
 
@@ -127,8 +160,9 @@ impl Automaton for MazeSolver{
         for x in 0..w {
             grid.set(x, 0, 1);
             grid.set(x, h - 1, 1);
-            if x < 60 {grid.set(x, 15, 1);}
-            if x > 30 {grid.set(x, 25, 1);}
+            // deux murs intérieurs décalés, proportionnels à la taille
+            if x < 3 * w / 4 {grid.set(x, h / 3, 1);}
+            if x > 3 * w / 8 {grid.set(x, 5 * h / 9, 1);}
         }
 
         for y in 0..h {
@@ -139,78 +173,52 @@ impl Automaton for MazeSolver{
         self.soft_init(grid);
     }
 
-    fn soft_init(&self, grid: &mut Grid) {
-        let w = grid.width();
-        let h = grid.height();
+    // Garde départ et arrivée s'ils sont déjà posés, sinon les place sur des cases libres.
+    fn soft_init(&mut self, grid: &mut Grid) {
+        let cells = grid.as_slice();
+        if cells.contains(&2) && cells.contains(&3) {
+            return;
+        }
+        Self::place_endpoints(grid);
+    }
 
-        grid.set(1, 1, 2);
-        grid.set(w-9, h-10, 3);
+    fn metrics(&self, grid: &Grid) -> Vec<(&'static str, f64)> {
+        let count = |f: fn(u8) -> bool| grid.as_slice().iter().filter(|&&v| f(v)).count() as f64;
+        vec![
+            ("explored", count(|v| v == 4 || v == 5 || v == 7)),
+            ("path", count(|v| v == 7)),
+        ]
     }
 
-    fn step(&self, current: &Grid, next: &mut Grid, async_fact: f32) {        
-        if async_fact == 0.0 {
-            let x = rng().random_range(..current.width());
-            let y = rng().random_range(..current.height());
-
-            let v = current.get(x, y);
-            next.set(x, y, v); // par défaut même valeur
-
-            // Exploration
-            if v == 0 && (Self::in_neighbors(current, x, y, 2) || Self::in_neighbors(current, x, y, 4)) {next.set(x, y, 4);}
-            if v == 4 {next.set(x, y, 5);}
-
-            if v == 4 && Self::in_neighbors(current, x, y, 3) {next.set(x, y, 7);}
-
-            // Backtracking
-            if v == 5 && Self::in_neighbors(current, x, y, 7) {next.set(x, y, 7);}
-            
-            // Suppression
-            let l = Self::get_4neigh_count(current, x, y);
-            if v == 7 {
-                if (l[1]+l[0])>=3 {next.set(x, y, 6);}
-                if (l[1]+l[0])>=2 && l[6]>=1 {next.set(x, y, 6);}
-                for pat in ALL_PATTERNS {
-                    if Self::match_pattern(current, x, y, pat){
-                        next.set(x, y, 6);
-                        break;
-                    }
-                }
-            }
-            if v == 6 {next.set(x, y, 0);}
-        } else {
-            for y in 0..current.height() {
-                for x in 0..current.width() {
-                    if rng().random::<f32>() < async_fact {
-                        let v = current.get(x, y);
-                        next.set(x, y, v); // par défaut même valeur
-        
-                        // Exploration
-                        if v == 0 && (Self::in_neighbors(current, x, y, 2) || Self::in_neighbors(current, x, y, 4)) {next.set(x, y, 4);}
-                        if v == 4 {next.set(x, y, 5);}
-        
-                        if v == 4 && Self::in_neighbors(current, x, y, 3) {next.set(x, y, 7);}
-        
-                        // Backtracking
-                        if v == 5 && Self::in_neighbors(current, x, y, 7) {next.set(x, y, 7);}
-                        
-                        // Suppression
-                        let l = Self::get_4neigh_count(current, x, y);
-                        if v == 7 {
-                            if (l[1]+l[0])>=3 {next.set(x, y, 6);}
-                            if (l[1]+l[0])>=2 && l[6]>=1 {next.set(x, y, 6);}
-                            for pat in ALL_PATTERNS {
-                                if Self::match_pattern(current, x, y, pat){
-                                    next.set(x, y, 6);
-                                    break;
-                                }
-                            }
-                        }
-                        if v == 6 {next.set(x, y, 0);}
-                    } else {
-                        next.set(x, y, current.get(x, y));
-                    }
-                }
-            }
+    // Chemin doré (7) présent et plus rien ne bouge.
+    fn is_finished(&self, grid: &Grid) -> bool {
+        grid.as_slice().contains(&7) && self.is_fixed_point(grid)
+    }
+
+    fn rule(&self, current: &Grid, x: usize, y: usize) -> u8 {
+        let v = current.get(x, y);
+        let mut out = v; // par défaut même valeur
+
+        // Exploration
+        if v == 0 && (Self::in_neighbors(current, x, y, 2) || Self::in_neighbors(current, x, y, 4)) {out = 4;}
+        if v == 4 {out = 5;}
+
+        if v == 4 && Self::in_neighbors(current, x, y, 3) {out = 7;}
+
+        // Backtracking
+        if v == 5 && Self::in_neighbors(current, x, y, 7) {out = 7;}
+
+        // Suppression
+        let l = Self::get_4neigh_count(current, x, y);
+        if v == 7 {
+            if (l[1]+l[0])>=3 {out = 6;}
+            if (l[1]+l[0])>=2 && l[6]>=1 {out = 6;}
         }
+        if v == 6 {out = 0;}
+
+        // Motifs (le premier qui correspond l'emporte)
+        if let Some(r) = self.patterns.apply(current, x, y) {out = r;}
+
+        out
     }
-}
\ No newline at end of file
+}
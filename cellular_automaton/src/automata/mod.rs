@@ -3,16 +3,46 @@ pub mod life;
 pub mod mazesolver;
 pub mod patterns;
 pub mod mazegenerator;
+pub mod margolus;
+pub mod elementary;
+pub mod wireworld;
+pub mod turmite;
 
 
 use crate::automaton::Automaton;
+use mazegenerator::MazeAlgorithm;
 
 pub fn available() -> Vec<Box<dyn Automaton>> {
     vec![
         Box::new(dummy::Dummy),
-        Box::new(life::Life),
-        Box::new(mazesolver::MazeSolver),
+        Box::new(life::Life::conway()),
+        Box::new(life::Life::maze()),
+        Box::new(life::Life::mazectric()),
+        Box::new(life::Life::cave(5)),
+        Box::new(mazesolver::MazeSolver::new()),
         Box::new(mazegenerator::MazeGenerator::new()),
+        Box::new(mazegenerator::MazeGenerator::with_algorithm(MazeAlgorithm::Prim)),
+        Box::new(mazegenerator::MazeGenerator::with_algorithm(MazeAlgorithm::Kruskal)),
+        Box::new(mazegenerator::MazeGenerator::with_algorithm(MazeAlgorithm::Wilson)),
+        Box::new(mazegenerator::MazeGenerator::with_algorithm(MazeAlgorithm::AldousBroder)),
+        Box::new(mazegenerator::MazeGenerator::with_algorithm(MazeAlgorithm::Eller)),
+        Box::new(mazegenerator::MazeGenerator::with_algorithm(MazeAlgorithm::Division)),
+        Box::new(mazegenerator::MazeGenerator::with_algorithm(MazeAlgorithm::BinaryTree)),
+        Box::new(mazegenerator::MazeGenerator::with_algorithm(MazeAlgorithm::GrowingTree(0.5))),
+        Box::new(mazegenerator::MazeGenerator::with_algorithm(MazeAlgorithm::Braided(0.5))),
+        Box::new(margolus::billiard_ball()),
+        Box::new(margolus::critters()),
+        Box::new(margolus::tron()),
+        Box::new(margolus::sand()),
+        Box::new(elementary::Elementary::wolfram(30)),
+        Box::new(elementary::Elementary::wolfram(90)),
+        Box::new(elementary::Elementary::wolfram(110)),
+        Box::new(elementary::Elementary::wolfram(184).with_random_init()),
+        Box::new(elementary::Elementary::totalistic(3, 1599).expect("code valide")),
+        Box::new(wireworld::Wireworld),
+        Box::new(turmite::Turmite::langton()),
+        Box::new(turmite::Turmite::from_letters("LLRR").expect("règle valide")),
+        Box::new(turmite::Turmite::from_spec("{{{1,8,1},{1,8,1}},{{1,2,1},{0,1,0}}}").expect("règle valide")),
     ]
 }
 
@@ -20,4 +50,9 @@ pub fn by_name(name: &str) -> Option<Box<dyn Automaton>> {
     available()
         .into_iter()
         .find(|a| a.name().eq_ignore_ascii_case(name))
+        .or_else(|| elementary::Elementary::parse(name).map(|a| Box::new(a) as Box<dyn Automaton>))
+        .or_else(|| turmite::Turmite::parse(name).map(|a| Box::new(a) as Box<dyn Automaton>))
+        .or_else(|| mazegenerator::MazeGenerator::parse(name).map(|a| Box::new(a) as Box<dyn Automaton>))
+        .or_else(|| mazesolver::MazeSolver::parse(name).map(|a| Box::new(a) as Box<dyn Automaton>))
+        .or_else(|| life::Life::parse(name).map(|a| Box::new(a) as Box<dyn Automaton>))
 }
@@ -1,39 +1,336 @@
-pub type Pattern5 = [[i8; 5]; 5];
-
-pub const BLANK_PATTERN: Pattern5 = [
-    [-1, -1, -1, -1, -1],
-    [-1, -1, -1, -1, -1],
-    [-1, -1, -1, -1, -1],
-    [-1, -1, -1, -1, -1],
-    [-1, -1, -1, -1, -1],
-];
-
-pub const CUTTING_CORNERS_1: Pattern5 = [
-    [-1, -1, -1, -1, -1],
-    [-1, -1,  1, -1, -1],
-    [-1,  7,  7,  1, -1],
-    [-1,  7,  7, -1, -1],
-    [-1, -1, -1, -1, -1],
-];
-
-pub const CUTTING_CORNERS_2: Pattern5 = [
-    [-1, -1, -1, -1, -1],
-    [-1, -1,  1, -1, -1],
-    [-1,  7,  7,  0, -1],
-    [-1,  7,  7, -1, -1],
-    [-1, -1, -1, -1, -1],
-];
-
-pub const CUTTING_CORNERS_3: Pattern5 = [
-    [-1, -1, -1, -1, -1],
-    [-1, -1,  0, -1, -1],
-    [-1,  7,  7,  0, -1],
-    [-1,  7,  7, -1, -1],
-    [-1, -1, -1, -1, -1],
-];
-
-pub const ALL_PATTERNS: &[Pattern5] = &[
-    CUTTING_CORNERS_1,
-    CUTTING_CORNERS_2,
-    CUTTING_CORNERS_3,
-];
\ No newline at end of file
+use std::{collections::HashMap, fmt, fs, path::Path, sync::Mutex};
+
+use crate::grid::{Grid, Transform, D4};
+
+// Motifs de voisinage N×M (dimensions impaires, centrés sur la case) avec jokers, ensembles
+// d'états et valeur de remplacement. Format texte : voir `automata/mazesolver.pat`.
+//
+// À la compilation les symétries sont développées une fois pour toutes (variantes en double
+// retirées). Le résultat pour une case ne dépend que des valeurs sur l'empreinte (union des
+// cases de toutes les variantes) : ces valeurs, ramenées à leur classe, sont empaquetées en
+// une clé, et le résultat est mémorisé par clé. Seule la première case d'un voisinage donné
+// parcourt les motifs ; une case dont l'état n'est accepté au centre d'aucun motif est
+// écartée avant tout calcul de clé.
+
+// États acceptés par une case, en bits (états 0..64).
+type StateSet = u64;
+const MAX_STATES: usize = 64;
+
+// (dx, dy, états acceptés) par rapport au centre
+type Cell = (i32, i32, StateSet);
+// rangées lues, None pour un joker
+type Rows = Vec<Vec<Option<StateSet>>>;
+// voisinage empaqueté
+type Key = u128;
+// au-delà, on vide le cache des clés
+const MAX_KEYS: usize = 1 << 16;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Symmetry {
+    None,
+    Rot,
+    D4,
+}
+
+impl Symmetry {
+    // Transformations (dx, dy) -> (dx', dy') du groupe.
+    fn transforms(self) -> &'static [Transform] {
+        match self {
+            Symmetry::None => &D4[..1],
+            Symmetry::Rot => &D4[..4],
+            Symmetry::D4 => &D4,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Pattern {
+    pub name: String,
+    pub symmetry: Symmetry,
+    // jokers exclus
+    cells: Vec<Cell>,
+    pub replace: u8,
+}
+
+#[derive(Debug)]
+pub struct PatternError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // ligne 0 : erreur sur le fichier entier
+        match self.line {
+            0 => write!(f, "{}", self.message),
+            l => write!(f, "ligne {l}: {}", self.message),
+        }
+    }
+}
+
+fn err(line: usize, message: impl Into<String>) -> PatternError {
+    PatternError { line, message: message.into() }
+}
+
+// Une case du motif : None pour un joker.
+fn parse_cell(token: &str, line: usize) -> Result<Option<StateSet>, PatternError> {
+    let state = |s: &str| -> Result<StateSet, PatternError> {
+        match s.trim().parse::<usize>() {
+            Ok(v) if v < MAX_STATES => Ok(1 << v),
+            _ => Err(err(line, format!("état invalide: {s:?}"))),
+        }
+    };
+
+    if token == "." {
+        Ok(None)
+    } else if let Some(s) = token.strip_prefix('!') {
+        Ok(Some(!state(s)?))
+    } else if let Some(list) = token.strip_prefix('{').and_then(|t| t.strip_suffix('}')) {
+        list.split(',').try_fold(0, |acc, s| Ok(acc | state(s)?)).map(Some)
+    } else {
+        state(token).map(Some)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct PatternSet {
+    patterns: Vec<Pattern>,
+    // (index du motif, cases de la variante), dans l'ordre du fichier
+    variants: Vec<(usize, Vec<Cell>)>,
+    // états acceptés au centre par au moins un motif
+    centers: StateSet,
+    // cases lues pour former la clé, None si elle ne tient pas dans une `Key`
+    footprint: Option<Vec<(i32, i32)>>,
+    // les états 0..top sont distingués ; au-delà, tous les motifs les traitent pareil
+    top: u8,
+    bits: u32,
+    // clé du voisinage -> remplacement
+    buckets: Mutex<HashMap<Key, Option<u8>>>,
+}
+
+impl Clone for PatternSet {
+    fn clone(&self) -> Self {
+        Self {
+            patterns: self.patterns.clone(),
+            variants: self.variants.clone(),
+            centers: self.centers,
+            footprint: self.footprint.clone(),
+            top: self.top,
+            bits: self.bits,
+            buckets: Mutex::default(),
+        }
+    }
+}
+
+impl PatternSet {
+    pub fn parse(text: &str) -> Result<Self, PatternError> {
+        let mut patterns = Vec::new();
+        // motif en cours : (ligne, nom, symétrie, rangées)
+        let mut open: Option<(usize, String, Symmetry, Rows)> = None;
+
+        for (i, raw) in text.lines().enumerate() {
+            let line = i + 1;
+            let l = raw.split('#').next().unwrap_or("").trim();
+            if l.is_empty() {
+                continue;
+            }
+
+            let words: Vec<&str> = l.split_whitespace().collect();
+            if let ["pattern", words @ ..] = words.as_slice() {
+                if let Some((start, ..)) = &open {
+                    return Err(err(*start, "motif sans `->`"));
+                }
+                let symmetry = match words {
+                    [_] => Symmetry::D4,
+                    [_, "sym", "d4"] => Symmetry::D4,
+                    [_, "sym", "rot"] => Symmetry::Rot,
+                    [_, "sym", "none"] => Symmetry::None,
+                    _ => return Err(err(line, "attendu: pattern <nom> [sym d4|rot|none]")),
+                };
+                open = Some((line, words[0].to_string(), symmetry, Vec::new()));
+            } else if let Some(value) = l.strip_prefix("->") {
+                let Some((start, name, symmetry, rows)) = open.take() else {
+                    return Err(err(line, "`->` hors d'un motif"));
+                };
+                let replace = value.trim().parse::<u8>().map_err(|_| err(line, "valeur de remplacement invalide"))?;
+                patterns.push(Self::build(start, name, symmetry, rows, replace)?);
+            } else {
+                let Some((_, _, _, rows)) = &mut open else {
+                    return Err(err(line, "rangée hors d'un motif"));
+                };
+                let row = l.split_whitespace().map(|t| parse_cell(t, line)).collect::<Result<Vec<_>, _>>()?;
+                rows.push(row);
+            }
+        }
+
+        if let Some((start, ..)) = open {
+            return Err(err(start, "motif sans `->`"));
+        }
+        Ok(Self::compile(patterns))
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, PatternError> {
+        let text = fs::read_to_string(path).map_err(|e| err(0, e.to_string()))?;
+        Self::parse(&text)
+    }
+
+    fn build(line: usize, name: String, symmetry: Symmetry, rows: Rows, replace: u8) -> Result<Pattern, PatternError> {
+        let h = rows.len();
+        let w = rows.first().map_or(0, |r| r.len());
+        if h.is_multiple_of(2) || w.is_multiple_of(2) || rows.iter().any(|r| r.len() != w) {
+            return Err(err(line, format!("{name}: il faut un rectangle de dimensions impaires")));
+        }
+
+        let (cx, cy) = ((w / 2) as i32, (h / 2) as i32);
+        let cells = rows.iter().enumerate()
+            .flat_map(|(j, row)| row.iter().enumerate().filter_map(move |(i, c)| c.map(|s| (i as i32 - cx, j as i32 - cy, s))))
+            .collect();
+        Ok(Pattern { name, symmetry, cells, replace })
+    }
+
+    fn compile(patterns: Vec<Pattern>) -> Self {
+        let mut variants = Vec::new();
+        for (p, pattern) in patterns.iter().enumerate() {
+            let mut seen: Vec<Vec<Cell>> = Vec::new();
+            for t in pattern.symmetry.transforms() {
+                let mut v: Vec<_> = pattern.cells.iter().map(|&(x, y, s)| { let (x, y) = t(x, y); (x, y, s) }).collect();
+                v.sort_unstable();
+                if !seen.contains(&v) {
+                    seen.push(v);
+                }
+            }
+            variants.extend(seen.into_iter().map(|v| (p, v)));
+        }
+
+        let centers = patterns.iter()
+            .map(|p| p.cells.iter().find(|c| c.0 == 0 && c.1 == 0).map_or(StateSet::MAX, |c| c.2))
+            .fold(0, |acc, s| acc | s);
+
+        // plus petit top tel que chaque ensemble soit uniforme sur top..64
+        let sets = || variants.iter().flat_map(|(_, v): &(usize, Vec<Cell>)| v.iter().map(|c| c.2));
+        let top = (0..MAX_STATES)
+            .find(|&m| { let high = StateSet::MAX << m; sets().all(|s| s & high == 0 || s & high == high) })
+            .unwrap_or(MAX_STATES);
+        // classes 0..top, top (autres états < 64), top + 1 (hors format)
+        let bits = (top as u32 + 2).next_power_of_two().trailing_zeros();
+
+        let mut offsets: Vec<(i32, i32)> = variants.iter().flat_map(|(_, v)| v.iter().map(|c| (c.0, c.1))).collect();
+        offsets.sort_unstable();
+        offsets.dedup();
+        let footprint = (offsets.len() as u32 * bits <= Key::BITS).then_some(offsets);
+
+        Self { patterns, variants, centers, footprint, top: top as u8, bits, buckets: Mutex::default() }
+    }
+
+    pub fn patterns(&self) -> &[Pattern] {
+        &self.patterns
+    }
+
+    // Valeur de remplacement du premier motif (dans l'ordre du fichier) qui correspond en (x, y).
+    pub fn apply(&self, g: &Grid, x: usize, y: usize) -> Option<u8> {
+        let center = g.get(x, y) as usize;
+        if center >= MAX_STATES || self.centers & (1 << center) == 0 {
+            return None;
+        }
+        let at = |dx: i32, dy: i32| g.get_wrapped(x as isize + dx as isize, y as isize + dy as isize);
+        let scan = || {
+            self.variants.iter()
+                .find(|(_, cells)| cells.iter().all(|&(dx, dy, s)| {
+                    let v = at(dx, dy);
+                    (v as usize) < MAX_STATES && s & (1 << v) != 0
+                }))
+                .map(|&(p, _)| self.patterns[p].replace)
+        };
+
+        let Some(footprint) = &self.footprint else {
+            return scan();
+        };
+        let key = footprint.iter().fold(0, |key: Key, &(dx, dy)| {
+            let v = at(dx, dy);
+            let class = if v < self.top { v } else if (v as usize) < MAX_STATES { self.top } else { self.top + 1 };
+            key << self.bits | class as Key
+        });
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(&r) = buckets.get(&key) {
+            return r;
+        }
+        let r = scan();
+        if buckets.len() >= MAX_KEYS {
+            buckets.clear();
+        }
+        buckets.insert(key, r);
+        r
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "
+pattern p sym rot
+. 1 .
+. 2 !0
+. . .
+-> 5
+";
+
+    // Même résultat que le parcours direct des variantes, dès la première case et depuis le cache.
+    #[test]
+    fn neighbourhood_keys() {
+        let set = PatternSet::parse(TEXT).expect("motif valide");
+        assert_eq!(set.variants.len(), 4);
+        // 0, 1, 2 distingués ; les autres états ne font qu'une classe
+        assert_eq!(set.top, 3);
+
+        let mut g = Grid::new(12, 12);
+        let mut put = |x: usize, y: usize, up: u8, right: u8| {
+            g.set(x, y, 2);
+            g.set(x, y - 1, up);
+            g.set(x + 1, y, right);
+        };
+        put(1, 1, 1, 9);
+        put(5, 1, 1, 12);
+        put(9, 1, 1, 0);
+        put(1, 5, 1, 200);
+        // variante tournée : 1 à droite, autre chose que 0 en bas
+        g.set(5, 5, 2);
+        g.set(6, 5, 1);
+        g.set(5, 6, 3);
+
+        for _ in 0..2 {
+            assert_eq!(set.apply(&g, 1, 1), Some(5));
+            assert_eq!(set.apply(&g, 5, 1), Some(5));
+            assert_eq!(set.apply(&g, 9, 1), None);
+            assert_eq!(set.apply(&g, 1, 5), None);
+            assert_eq!(set.apply(&g, 5, 5), Some(5));
+            assert_eq!(set.apply(&g, 8, 8), None);
+        }
+    }
+
+    #[test]
+    fn keyword_must_stand_alone() {
+        assert!(PatternSet::parse("patternfoo sym rot\n1\n-> 2").is_err());
+        let set = PatternSet::parse("pattern   foo sym rot\n1\n-> 2").expect("motif valide");
+        assert_eq!(set.patterns()[0].name, "foo");
+        assert_eq!(set.patterns()[0].symmetry, Symmetry::Rot);
+    }
+
+    // Une case dont l'état ne peut être au centre d'aucun motif n'ajoute pas de clé.
+    #[test]
+    fn centre_prefilter() {
+        let set = PatternSet::parse(TEXT).expect("motif valide");
+        let mut g = Grid::new(6, 6);
+        for x in 0..6 {
+            assert_eq!(set.apply(&g, x, 3), None);
+        }
+        g.set(2, 2, 200);
+        assert_eq!(set.apply(&g, 2, 2), None);
+        assert!(set.buckets.lock().expect("verrou").is_empty());
+
+        g.set(2, 2, 2);
+        assert_eq!(set.apply(&g, 2, 2), None);
+        assert_eq!(set.buckets.lock().expect("verrou").len(), 1);
+    }
+}
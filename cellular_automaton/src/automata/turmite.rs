@@ -0,0 +1,310 @@
+use crate::{automaton::{Automaton, StateInfo}, grid::Grid};
+
+// Virage relatif, codé comme dans Golly : 1 tout droit, 2 droite, 4 demi-tour, 8 gauche.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Turn {
+    None,
+    Right,
+    UTurn,
+    Left,
+}
+
+impl Turn {
+    fn from_code(c: u32) -> Option<Self> {
+        match c {
+            1 => Some(Turn::None),
+            2 => Some(Turn::Right),
+            4 => Some(Turn::UTurn),
+            8 => Some(Turn::Left),
+            _ => None,
+        }
+    }
+
+    fn from_letter(c: char) -> Option<Self> {
+        match c.to_ascii_uppercase() {
+            'N' => Some(Turn::None),
+            'R' => Some(Turn::Right),
+            'U' => Some(Turn::UTurn),
+            'L' => Some(Turn::Left),
+            _ => None,
+        }
+    }
+
+    // direction : 0 nord, 1 est, 2 sud, 3 ouest
+    fn apply(self, dir: u8) -> u8 {
+        match self {
+            Turn::None => dir,
+            Turn::Right => (dir + 1) % 4,
+            Turn::UTurn => (dir + 2) % 4,
+            Turn::Left => (dir + 3) % 4,
+        }
+    }
+}
+
+// Transition pour un couple (état de la fourmi, couleur de la case).
+#[derive(Clone, Copy, Debug)]
+pub struct Action {
+    pub write: u8,
+    pub turn: Turn,
+    pub next: usize,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Ant {
+    pub x: usize,
+    pub y: usize,
+    pub dir: u8,
+    pub state: usize,
+}
+
+// Turmite : fourmis qui se déplacent sur la grille (couleurs = états des cellules).
+// La table est indexée par [état][couleur]. La position des fourmis vit hors de la grille.
+#[derive(Clone)]
+pub struct Turmite {
+    name: String,
+    table: Vec<Vec<Action>>,
+    ants: Vec<Ant>,
+}
+
+impl Turmite {
+    pub fn new(name: impl Into<String>, table: Vec<Vec<Action>>) -> Self {
+        Self { name: name.into(), table, ants: Vec::new() }
+    }
+
+    // Fourmi de Langton (RL).
+    pub fn langton() -> Self {
+        let mut t = Self::from_letters("RL").expect("règle valide");
+        t.name = "langton".to_string();
+        t
+    }
+
+    // Fourmi à plusieurs couleurs : "RL", "LLRR"... la couleur i devient i+1
+    // et la fourmi tourne selon la i-ème lettre (L, R, N, U).
+    pub fn from_letters(rule: &str) -> Option<Self> {
+        let n = rule.chars().count();
+        if !(2..=8).contains(&n) {
+            return None;
+        }
+        let row = rule
+            .chars()
+            .enumerate()
+            .map(|(i, c)| Some(Action { write: ((i + 1) % n) as u8, turn: Turn::from_letter(c)?, next: 0 }))
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self::new(format!("turmite:{}", rule.to_ascii_uppercase()), vec![row]))
+    }
+
+    // Notation Golly : {{{écrit, virage, état suivant}, ...par couleur}, ...par état}
+    // ex: {{{1,2,0},{0,8,0}}} est la fourmi de Langton.
+    pub fn from_spec(spec: &str) -> Option<Self> {
+        let mut table: Vec<Vec<Action>> = Vec::new();
+        let mut state: Vec<Action> = Vec::new();
+        let mut triple: Vec<u32> = Vec::new();
+        let mut num: Option<u32> = None;
+        let mut depth = 0;
+
+        for c in spec.chars() {
+            if let Some(d) = c.to_digit(10) {
+                num = Some(num.unwrap_or(0).checked_mul(10)?.checked_add(d)?);
+                continue;
+            }
+            if let Some(n) = num.take() {
+                if depth != 3 { return None; }
+                triple.push(n);
+            }
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    match depth {
+                        3 => {
+                            let &[write, turn, next] = &triple[..] else { return None };
+                            state.push(Action { write: u8::try_from(write).ok()?, turn: Turn::from_code(turn)?, next: next as usize });
+                            triple.clear();
+                        }
+                        2 => table.push(std::mem::take(&mut state)),
+                        1 => {}
+                        _ => return None,
+                    }
+                    depth -= 1;
+                }
+                ',' | ' ' => {}
+                _ => return None,
+            }
+        }
+
+        // même nombre de couleurs partout, cohérent avec les valeurs écrites
+        let colors = table.first()?.len();
+        if depth != 0 || !(1..=8).contains(&colors) {
+            return None;
+        }
+        for row in &table {
+            if row.len() != colors { return None; }
+            for a in row {
+                if a.write as usize >= colors || a.next >= table.len() { return None; }
+            }
+        }
+
+        Some(Self::new(format!("turmite:{}", spec.replace(' ', "")), table))
+    }
+
+    // "langton", "turmite:LLRR", "turmite:{{{1,2,0},{0,8,0}}}"
+    pub fn parse(name: &str) -> Option<Self> {
+        if name.eq_ignore_ascii_case("langton") {
+            return Some(Self::langton());
+        }
+        let rule = name.strip_prefix("turmite:")?;
+        if rule.starts_with('{') {
+            Self::from_spec(rule)
+        } else {
+            Self::from_letters(rule)
+        }
+    }
+
+    pub fn ants(&self) -> &[Ant] {
+        &self.ants
+    }
+
+    fn colors(&self) -> u8 {
+        self.table[0].len() as u8
+    }
+}
+
+impl Automaton for Turmite {
+    fn name(&self) -> &str { &self.name }
+
+    // couleurs des cases (les fourmis vivent hors de la grille)
+    fn states(&self) -> Vec<StateInfo> {
+        StateInfo::levels(self.colors())
+    }
+
+    fn init(&mut self, grid: &mut Grid) {
+        grid.fill(0);
+        self.soft_init(grid);
+    }
+
+    // Une fourmi au centre, tournée vers le nord.
+    fn soft_init(&mut self, grid: &mut Grid) {
+        self.ants = vec![Ant { x: grid.width() / 2, y: grid.height() / 2, dir: 0, state: 0 }];
+    }
+
+    fn reset(&mut self) {
+        self.ants.clear();
+    }
+
+    // par fourmi : x, y (u32 LE), dir (u8), état (u32 LE)
+    fn save_state(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.ants.len() * 13);
+        for a in &self.ants {
+            out.extend_from_slice(&(a.x as u32).to_le_bytes());
+            out.extend_from_slice(&(a.y as u32).to_le_bytes());
+            out.push(a.dir);
+            out.extend_from_slice(&(a.state as u32).to_le_bytes());
+        }
+        out
+    }
+
+    fn load_state(&mut self, data: &[u8]) -> bool {
+        if !data.len().is_multiple_of(13) { return false; }
+
+        let u32_at = |c: &[u8], i: usize| u32::from_le_bytes([c[i], c[i + 1], c[i + 2], c[i + 3]]) as usize;
+        let ants: Vec<Ant> = data
+            .chunks_exact(13)
+            .map(|c| Ant { x: u32_at(c, 0), y: u32_at(c, 4), dir: c[8] % 4, state: u32_at(c, 9) })
+            .collect();
+        if ants.iter().any(|a| a.state >= self.table.len()) { return false; }
+
+        self.ants = ants;
+        true
+    }
+
+    fn is_local(&self) -> bool { false }
+
+    fn step(&mut self, current: &Grid, next: &mut Grid) {
+        next.as_mut_slice().copy_from_slice(current.as_slice());
+
+        let w = next.width();
+        let h = next.height();
+        let colors = self.colors();
+
+        for ant in &mut self.ants {
+            // la grille a pu changer de taille depuis le placement
+            ant.x %= w;
+            ant.y %= h;
+
+            let c = next.get(ant.x, ant.y).min(colors - 1);
+            let a = self.table[ant.state][c as usize];
+
+            next.set(ant.x, ant.y, a.write);
+            ant.dir = a.turn.apply(ant.dir);
+            ant.state = a.next;
+
+            // avance d'une case (tore)
+            match ant.dir {
+                0 => ant.y = if ant.y == 0 { h - 1 } else { ant.y - 1 },
+                1 => ant.x = if ant.x + 1 == w { 0 } else { ant.x + 1 },
+                2 => ant.y = if ant.y + 1 == h { 0 } else { ant.y + 1 },
+                _ => ant.x = if ant.x == 0 { w - 1 } else { ant.x - 1 },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(t: &mut Turmite, g: &mut Grid, steps: usize) {
+        let mut next = g.clone();
+        for _ in 0..steps {
+            t.step(g, &mut next);
+            std::mem::swap(g, &mut next);
+        }
+    }
+
+    #[test]
+    fn langton_first_steps() {
+        let mut ant = Turmite::langton();
+        let mut g = Grid::new(16, 16);
+        ant.init(&mut g);
+
+        // quatre virages à droite : carré 2×2 noir, retour au départ vers le nord
+        run(&mut ant, &mut g, 4);
+        assert_eq!(g.as_slice().iter().filter(|&&v| v == 1).count(), 4);
+        let a = ant.ants()[0];
+        assert_eq!((a.x, a.y, a.dir), (8, 8, 0));
+        run(&mut ant, &mut g, 1);
+        assert_eq!(g.as_slice().iter().filter(|&&v| v == 1).count(), 3);
+    }
+
+    #[test]
+    fn langton_builds_its_highway() {
+        let mut ant = Turmite::langton();
+        let mut g = Grid::new(256, 256);
+        ant.init(&mut g);
+        run(&mut ant, &mut g, 11_000);
+
+        // autoroute : période 104, deux cases en diagonale
+        let a = ant.ants()[0];
+        run(&mut ant, &mut g, 104);
+        let b = ant.ants()[0];
+        assert_eq!((a.x.abs_diff(b.x), a.y.abs_diff(b.y)), (2, 2));
+        assert_eq!(a.dir, b.dir);
+    }
+
+    #[test]
+    fn parse() {
+        let mut spec = Turmite::parse("turmite:{{{1,2,0},{0,8,0}}}").expect("règle valide");
+        let mut letters = Turmite::parse("turmite:rl").expect("règle valide");
+        let (mut a, mut b) = (Grid::new(32, 32), Grid::new(32, 32));
+        spec.init(&mut a);
+        letters.init(&mut b);
+        run(&mut spec, &mut a, 500);
+        run(&mut letters, &mut b, 500);
+        assert_eq!(a.as_slice(), b.as_slice());
+
+        assert_eq!(Turmite::parse("LANGTON").map(|t| t.name), Some("langton".to_string()));
+        for bad in ["turmite:R", "turmite:RX", "turmite:{{{1,3,0},{0,8,0}}}", "turmite:{{{2,2,0},{0,8,0}}}",
+                    "turmite:{{{1,2,1},{0,8,0}}}", "turmite:{{{1,2,0},{0,8}}}", "turmite:{{{1,2,0}}", "rl"] {
+            assert!(Turmite::parse(bad).is_none(), "{bad}");
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{automaton::{Automaton, StateInfo}, grid::Grid};
+
+#[derive(Clone)]
+pub struct Wireworld;
+
+impl Wireworld {
+    #[inline]
+    fn count_heads(cur: &Grid, x: usize, y: usize) -> u8 {
+        let w = cur.width();
+        let h = cur.height();
+
+        // tore (wrap)
+        let xm1 = if x == 0 { w - 1 } else { x - 1 };
+        let xp1 = if x + 1 == w { 0 } else { x + 1 };
+        let ym1 = if y == 0 { h - 1 } else { y - 1 };
+        let yp1 = if y + 1 == h { 0 } else { y + 1 };
+
+        let mut n = 0u8;
+        for (nx, ny) in [(xm1, ym1), (x, ym1), (xp1, ym1), (xm1, y), (xp1, y), (xm1, yp1), (x, yp1), (xp1, yp1)] {
+            n += (cur.get(nx, ny) == 1) as u8;
+        }
+        n
+    }
+
+    // Horloge : boucle rectangulaire avec un électron, qui alimente un fil vers la droite.
+    fn clock(grid: &mut Grid, x0: usize, y0: usize, len: usize) {
+        let w = grid.width();
+        for x in x0..x0 + 6 {
+            grid.set(x, y0, 3);
+            grid.set(x, y0 + 2, 3);
+        }
+        grid.set(x0, y0 + 1, 3);
+        grid.set(x0 + 5, y0 + 1, 3);
+        for x in x0 + 6..(x0 + 6 + len).min(w) {
+            grid.set(x, y0 + 1, 3);
+        }
+        grid.set(x0 + 1, y0, 2);
+        grid.set(x0 + 2, y0, 1);
+    }
+}
+
+impl Automaton for Wireworld {
+    fn name(&self) -> &str { "wireworld" }
+
+    fn states(&self) -> Vec<StateInfo> {
+        vec![
+            StateInfo::new("vide", [0, 0, 0], ' ', "aucun conducteur"),
+            StateInfo::new("tête", [60, 120, 255], '@', "tête d'électron"),
+            StateInfo::new("queue", [255, 80, 40], '~', "queue d'électron"),
+            StateInfo::new("conducteur", [230, 180, 40], '#', "fil : devient tête s'il a 1 ou 2 têtes voisines"),
+        ]
+    }
+
+    // les deux horloges (6×3) posées par `init`, en h/3 et 2h/3 : sans chevauchement dès h = 9
+    fn min_size(&self) -> (usize, usize) {
+        (8, 9)
+    }
+
+    fn init(&mut self, grid: &mut Grid) {
+        grid.fill(0);
+
+        let h = grid.height();
+        let w = grid.width();
+        Self::clock(grid, 2, h / 3, w / 2);
+        Self::clock(grid, 2, 2 * h / 3, w / 3);
+    }
+
+    fn soft_init(&mut self, _grid: &mut Grid) {}
+
+    // Plus aucun électron.
+    fn is_finished(&self, grid: &Grid) -> bool {
+        !grid.as_slice().iter().any(|&v| v == 1 || v == 2)
+    }
+
+    fn rule(&self, current: &Grid, x: usize, y: usize) -> u8 {
+        match current.get(x, y) {
+            1 => 2,
+            2 => 3,
+            3 => {
+                let n = Self::count_heads(current, x, y);
+                if n == 1 || n == 2 { 1 } else { 3 }
+            }
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clocks_do_not_overlap() {
+        let (w, h0) = Wireworld.min_size();
+        for h in h0..=30 {
+            let mut g = Grid::new(w, h);
+            Wireworld.init(&mut g);
+            // deux horloges de 14 cases, sans fil à cette largeur
+            assert_eq!(g.as_slice().iter().filter(|&&v| v != 0).count(), 28, "hauteur {h}");
+            assert_eq!(g.as_slice().iter().filter(|&&v| v == 1).count(), 2, "hauteur {h}");
+        }
+    }
+}
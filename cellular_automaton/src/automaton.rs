@@ -1,14 +1,131 @@
 use crate::grid::Grid;
 
-pub trait Automaton {
-    fn name(&self) -> &'static str;
+// Description d'un état de cellule : légende, couleur (UI et terminal), glyphe, peinture.
+#[derive(Clone, Debug)]
+pub struct StateInfo {
+    pub name: String,
+    pub description: String,
+    pub color: [u8; 3],
+    pub glyph: char,
+    // l'utilisateur peut le poser à la souris
+    pub paintable: bool,
+}
+
+impl StateInfo {
+    pub fn new(name: &str, color: [u8; 3], glyph: char, description: &str) -> Self {
+        Self { name: name.to_string(), description: description.to_string(), color, glyph, paintable: true }
+    }
+
+    // État produit par l'automate seul (exploration, trajet...).
+    pub fn fixed(mut self) -> Self {
+        self.paintable = false;
+        self
+    }
+
+    // n niveaux de gris du noir au blanc, nommés par leur valeur.
+    pub fn levels(n: u8) -> Vec<Self> {
+        const GLYPHS: [char; 5] = [' ', '░', '▒', '▓', '█'];
+        let top = n.max(2) as usize - 1;
+        (0..n as usize)
+            .map(|i| {
+                let v = (255 * i / top) as u8;
+                Self::new(&i.to_string(), [v, v, v], GLYPHS[4 * i / top], &format!("état {i}"))
+            })
+            .collect()
+    }
+}
+
+pub trait Automaton: AutomatonClone + Send {
+    fn name(&self) -> &str;
+
+    // Plus petite grille (largeur, hauteur) sur laquelle l'automate fonctionne.
+    // L'engine refuse les grilles plus petites.
+    fn min_size(&self) -> (usize, usize) {
+        (1, 1)
+    }
+
+    // États que prend une cellule, indexés par leur valeur.
+    fn states(&self) -> Vec<StateInfo> {
+        StateInfo::levels(2)
+    }
 
     // Initialise la grille (seed).
-    fn init(&self, grid: &mut Grid);
+    fn init(&mut self, grid: &mut Grid);
 
     // Initialisation au chargement d'une grille
-    fn soft_init(&self, grid: &mut Grid);
+    fn soft_init(&mut self, grid: &mut Grid);
+
+    // Remet à zéro l'état propre à l'automate (pile, phase, fourmis...) sans toucher la grille.
+    fn reset(&mut self) {}
+
+    // État propre à l'automate, pour les fichiers de sauvegarde. Vide si sans état.
+    fn save_state(&self) -> Vec<u8> {
+        Vec::new()
+    }
+
+    // Restaure un état produit par `save_state`. Faux si les données sont invalides.
+    fn load_state(&mut self, data: &[u8]) -> bool {
+        data.is_empty()
+    }
+
+    // Transition locale : nouvel état de la cellule (x, y) vu depuis `current`.
+    // L'engine l'applique selon le schéma de mise à jour choisi.
+    fn rule(&self, current: &Grid, x: usize, y: usize) -> u8 {
+        current.get(x, y)
+    }
+
+    // Faux pour les automates qui ne se décrivent pas par une règle locale :
+    // l'engine appelle alors `step` quel que soit le schéma.
+    fn is_local(&self) -> bool {
+        true
+    }
+
+    // Calcule l'état suivant dans `next` à partir de `current` (automates non locaux).
+    // Peut faire évoluer un état propre à l'automate (ex: fourmis).
+    fn step(&mut self, current: &Grid, next: &mut Grid) {
+        next.as_mut_slice().copy_from_slice(current.as_slice());
+    }
+
+    // Mesures propres à l'automate pour les statistiques (ex: cases explorées du solveur).
+    fn metrics(&self, _grid: &Grid) -> Vec<(&'static str, f64)> {
+        Vec::new()
+    }
+
+    // Vrai quand l'exécution est terminée (labyrinthe creusé, chemin trouvé, vie figée...).
+    // L'engine se met alors en pause.
+    fn is_finished(&self, _grid: &Grid) -> bool {
+        false
+    }
+
+    // Vrai si aucune cellule ne changerait par `rule` : point fixe, quel que soit le schéma.
+    fn is_fixed_point(&self, grid: &Grid) -> bool {
+        (0..grid.height()).all(|y| (0..grid.width()).all(|x| self.rule(grid, x, y) == grid.get(x, y)))
+    }
+
+    // Vrai si `step_back` inverse exactement `step`.
+    fn is_reversible(&self) -> bool {
+        false
+    }
+
+    // Annule un pas : calcule dans `next` l'état qui précédait `current`.
+    fn step_back(&mut self, current: &Grid, next: &mut Grid) {
+        next.as_mut_slice().copy_from_slice(current.as_slice());
+    }
+}
+
+// Permet de cloner un `Box<dyn Automaton>` (historique de l'engine).
+pub trait AutomatonClone {
+    fn box_clone(&self) -> Box<dyn Automaton>;
+}
+
+impl<T: Automaton + Clone + 'static> AutomatonClone for T {
+    fn box_clone(&self) -> Box<dyn Automaton> {
+        Box::new(self.clone())
+    }
+}
 
-    // Calcule l'état suivant dans `next` à partir de `current`.
-    fn step(&self, current: &Grid, next: &mut Grid, async_fact: f32);
+impl Clone for Box<dyn Automaton> {
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
 }
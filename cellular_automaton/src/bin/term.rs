@@ -1,14 +1,20 @@
-use std::{thread, time::Duration};
+use std::{sync::atomic::AtomicUsize, thread, time::Duration};
 
 use cellular_automaton::{
+    analysis::{self, explore},
     automata,
+    io::png,
+    palette::Palette,
     engine::Engine,
     render::{terminal::TerminalRenderer, Renderer},
+    scheme::UpdateScheme,
+    seeding::{Placement, Seeding},
 };
 
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
+    let arg = |flag: &str| args.iter().position(|a| a == flag).and_then(|i| args.get(i + 1));
 
     if args.iter().any(|a| a == "--list") {
         for a in automata::available() {
@@ -17,17 +23,258 @@ fn main() {
         return;
     }
 
+    // --verify <n> [--seed <s>] : vérifie le solveur sur n labyrinthes aléatoires
+    if let Some(n) = args.iter().position(|a| a == "--verify").and_then(|i| args.get(i + 1)) {
+        let runs: usize = n.parse().unwrap_or(10);
+        let seed = arg("--seed").and_then(|s| s.parse().ok()).unwrap_or_else(rand::random);
+        let failures = analysis::path::check_random_mazes(runs, 41, 21, seed);
+        for f in &failures {
+            println!("{f}");
+        }
+        println!("{}/{runs} labyrinthes résolus correctement", runs - failures.len());
+        std::process::exit(if failures.is_empty() { 0 } else { 1 });
+    }
+
+    // --explore <min..max> [--soups <n>] [--gens <n>] [--seed <s>] [--threads <t>] [--csv <fichier>] :
+    // classe chaque règle B/S de l'intervalle (ex: B3/S23..B36/S236)
+    if let Some(spec) = arg("--explore") {
+        let range = explore::RuleRange::parse(spec).unwrap_or_else(|e| {
+            eprintln!("Intervalle invalide: {e}");
+            std::process::exit(2);
+        });
+        let mut config = explore::ExploreConfig::new(range);
+        if let Some(n) = arg("--soups").and_then(|s| s.parse().ok()) {
+            config.soups = n;
+        }
+        if let Some(n) = arg("--gens").and_then(|s| s.parse().ok()) {
+            config.generations = n;
+        }
+        if let Some(t) = arg("--threads").and_then(|s| s.parse().ok()) {
+            config.threads = t;
+        }
+        config.seed = arg("--seed").and_then(|s| s.parse().ok()).unwrap_or(0);
+        let reports = explore::run(&config, &AtomicUsize::new(0));
+        print!("{}", explore::to_table(&reports));
+        if let Some(path) = arg("--csv")
+            && let Err(e) = std::fs::write(path, explore::to_csv(&reports)) {
+            eprintln!("Export CSV: {e}");
+        }
+        return;
+    }
+
     let name = args.get(1).map(|s| s.as_str()).unwrap_or("dummy");
     let automaton = automata::by_name(name).unwrap_or_else(|| {
+        if let Some(Err(e)) = name.strip_prefix("mazesolver:").map(automata::mazesolver::MazeSolver::from_file) {
+            eprintln!("Motifs invalides: {e}");
+            std::process::exit(2);
+        }
         eprintln!("Automate inconnu: {name}\nUtilise --list");
         std::process::exit(2);
     });
 
-    let mut engine = Engine::new(40, 20, 1.0, automaton);
+    // --scheme <nom> [--alpha <α>]
+    let alpha = arg("--alpha").and_then(|s| s.parse().ok()).unwrap_or(1.0);
+    let scheme = match arg("--scheme") {
+        None => UpdateScheme::Synchronous,
+        Some(s) => UpdateScheme::by_name(s, alpha).unwrap_or_else(|| {
+            eprintln!("Schéma inconnu: {s}");
+            std::process::exit(2);
+        }),
+    };
+
+    // --census <n> [--seed <s>] [--threads <t>] [--csv <fichier>] : n soupes de la règle
+    // life-like donnée, table des objets obtenus (sans affichage de la grille)
+    if let Some(n) = arg("--census") {
+        let Some(rule) = analysis::census::rule_by_name(name) else {
+            eprintln!("{name}: pas une règle life-like");
+            std::process::exit(2);
+        };
+        let mut config = analysis::census::CensusConfig::new(rule, n.parse().unwrap_or(1000));
+        config.seed = arg("--seed").and_then(|s| s.parse().ok()).unwrap_or(0);
+        if let Some(t) = arg("--threads").and_then(|s| s.parse().ok()) {
+            config.threads = t;
+        }
+        let start = std::time::Instant::now();
+        let census = analysis::census::run(&config);
+        print!("{}", census.to_table());
+        println!("{} fils, {:.1} s", config.threads, start.elapsed().as_secs_f32());
+        if let Some(path) = arg("--csv")
+            && let Err(e) = std::fs::write(path, census.to_csv()) {
+            eprintln!("Export CSV: {e}");
+        }
+        return;
+    }
+
+    // --palette <nom|fichier> : couleurs du rendu et de l'image
+    let palette = match arg("--palette") {
+        None => Palette::default(),
+        Some(p) => Palette::by_name(p).unwrap_or_else(|e| {
+            eprintln!("Palette invalide: {e}");
+            std::process::exit(2);
+        }),
+    };
+    let states = |engine: &Engine| {
+        let mut s = engine.automaton().states();
+        palette.apply(&mut s);
+        s
+    };
+
+    // --png <fichier> : image de la dernière grille en sortie
+    let png_out = arg("--png");
+    let export_png = |engine: &Engine| {
+        if let Some(path) = png_out
+            && let Err(e) = png::save(path, engine.current(), &states(engine), 8) {
+            eprintln!("Export PNG: {e}");
+        }
+    };
+
+    // --csv <fichier> : statistiques par génération en sortie
+    let csv_out = arg("--csv");
+    // --spatial : entropies, corrélation et amas de la dernière grille (JSON) en sortie
+    let spatial = args.iter().any(|a| a == "--spatial");
+    let export = |engine: &Engine| {
+        export_png(engine);
+        if spatial {
+            println!("{}", analysis::spatial::SpatialReport::measure(engine.current()).to_json());
+        }
+        if let Some(path) = csv_out
+            && let Err(e) = std::fs::write(path, engine.stats().to_csv()) {
+            eprintln!("Export CSV: {e}");
+        }
+    };
+
+    // --stop-on-cycle : arrêt dès qu'une configuration revient (point fixe, oscillateur, vaisseau)
+    let stop_on_cycle = args.iter().any(|a| a == "--stop-on-cycle");
+
+    // --steps <n> : abandon après n pas
+    let max_steps: Option<u64> = arg("--steps").and_then(|s| s.parse().ok());
+
+    // --seed-with <graine> [--seed <n>] [--over] : condition initiale à la place de `init`
+    // (bernoulli:0.3, soup:16, d8:12, blobs:8:0.4, single, file:motif.rle...), ou par-dessus
+    let seed: Option<u64> = arg("--seed").and_then(|s| s.parse().ok());
+    let seeding = arg("--seed-with").map(|spec| {
+        let mut s = Seeding::parse(spec, seed.unwrap_or(0)).unwrap_or_else(|e| {
+            eprintln!("{e}");
+            std::process::exit(2);
+        });
+        if args.iter().any(|a| a == "--over") {
+            s.placement = Placement::Over;
+        }
+        s
+    });
+
+    // --sweep <α...> [--runs <m>] [--steps <t>] [--seed <s>] [--seed-with <graine>] [--threads <n>]
+    // [--csv <fichier>] : densité finale selon α (ex: 0.5:1:0.05), m exécutions de t pas par valeur
+    if let Some(spec) = arg("--sweep") {
+        let alphas = analysis::sweep::parse_alphas(spec).unwrap_or_else(|e| {
+            eprintln!("{e}");
+            std::process::exit(2);
+        });
+        let mut config = analysis::sweep::SweepConfig::new(name, alphas);
+        if let Some(m) = arg("--runs").and_then(|s| s.parse().ok()) {
+            config.runs = m;
+        }
+        if let Some(t) = max_steps {
+            config.steps = t;
+        }
+        if let Some(n) = arg("--threads").and_then(|s| s.parse().ok()) {
+            config.threads = n;
+        }
+        config.seed = seed.unwrap_or(0);
+        if let Some(s) = &seeding {
+            config.initial = s.seed.clone();
+        }
+        match analysis::sweep::run(&config, &AtomicUsize::new(0)) {
+            Ok(points) => {
+                let csv = analysis::sweep::to_csv(&config, &points);
+                print!("{csv}");
+                if let Some(path) = csv_out
+                    && let Err(e) = std::fs::write(path, csv) {
+                    eprintln!("Export CSV: {e}");
+                }
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(2);
+            }
+        }
+        return;
+    }
+
+    let mut engine = Engine::new(40, 20, scheme, automaton);
+    // même graine pour le schéma de mise à jour
+    if let Some(n) = seed {
+        engine.set_seed(n);
+    }
+    if seeding.is_some() {
+        engine.set_seeding(seeding);
+    }
+
+    // --second-order : règle enveloppée en automate réversible, x(t+1) = f(x(t)) - x(t-1) mod k
+    let verify: Option<u64> = arg("--verify-reversible").and_then(|s| s.parse().ok());
+    if (verify.is_some() || args.iter().any(|a| a == "--second-order")) && !engine.set_second_order(true) {
+        eprintln!("{name}: pas de règle locale, pas de second ordre");
+        std::process::exit(2);
+    }
+
+    // --verify-reversible <n> : n pas en avant puis n en arrière depuis la grille initiale
+    // (second ordre), sans affichage
+    if let Some(n) = verify {
+        let previous = engine.previous().expect("second ordre actif");
+        match cellular_automaton::reversible::verify(engine.automaton(), previous, engine.current(), n) {
+            Ok(()) => println!("{name}: réversible sur {n} pas"),
+            Err(0) => {
+                println!("{name}: l'aller-retour de {n} pas ne ramène pas à la grille initiale");
+                std::process::exit(1);
+            }
+            Err(g) => {
+                println!("{name}: inversion fausse à la génération {g}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    // --damage <n> : propagation d'un défaut au centre sur n pas (schéma et --seed courants),
+    // distance de Hamming par pas en CSV, sans affichage
+    if let Some(n) = arg("--damage").and_then(|s| s.parse().ok()) {
+        let g = engine.current();
+        let distances = analysis::spatial::damage_spreading(
+            engine.automaton(), g, engine.scheme(), seed.unwrap_or(0), (g.width() / 2, g.height() / 2), n, &AtomicUsize::new(0),
+        ).unwrap_or_else(|e| {
+            eprintln!("Propagation: {e}");
+            std::process::exit(2);
+        });
+        println!("step,hamming");
+        for (i, d) in distances.iter().enumerate() {
+            println!("{i},{d:.6}");
+        }
+        return;
+    }
+
     let mut renderer = TerminalRenderer::new();
 
+    // code de sortie : 0 si l'automate a terminé, 1 si --steps est atteint avant
     loop {
-        renderer.render(engine.current());
+        renderer.render(engine.current(), &states(&engine));
+        if let Some(n) = engine.finished() {
+            println!("{}: terminé en {n} pas", engine.automaton().name());
+            if engine.automaton().name().starts_with("mazesolver") {
+                println!("{}", analysis::path::analyse(engine.current()).to_json());
+            }
+            export(&engine);
+            std::process::exit(0);
+        }
+        if stop_on_cycle && let Some(c) = engine.cycle() {
+            println!("{}: {c}", engine.automaton().name());
+            export(&engine);
+            std::process::exit(0);
+        }
+        if max_steps.is_some_and(|m| engine.generation() >= m) {
+            println!("{}: non terminé après {} pas", engine.automaton().name(), engine.generation());
+            export(&engine);
+            std::process::exit(1);
+        }
         engine.step_once();
         thread::sleep(Duration::from_millis(80));
     }
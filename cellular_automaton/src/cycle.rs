@@ -0,0 +1,177 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::grid::Grid;
+
+// Détection de cycles : chaque génération est hachée (grille + état propre de l'automate),
+// une configuration déjà vue donne la période. Un second hachage, de la zone non nulle
+// recadrée, retrouve une même forme déplacée : vaisseau (hors passage du bord du tore).
+// N'a de sens que pour un automate et un schéma déterministes. Seules les `MAX_SEEN`
+// dernières configurations nouvelles sont gardées : une période plus longue passe inaperçue.
+
+const MAX_SEEN: usize = 4096;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Cycle {
+    pub period: u64,
+    // première génération du cycle
+    pub since: u64,
+    // déplacement par période (0, 0 : oscillateur ou point fixe)
+    pub dx: isize,
+    pub dy: isize,
+}
+
+impl Cycle {
+    pub fn is_still(&self) -> bool {
+        self.period == 1 && self.dx == 0 && self.dy == 0
+    }
+
+    pub fn is_spaceship(&self) -> bool {
+        self.dx != 0 || self.dy != 0
+    }
+}
+
+impl std::fmt::Display for Cycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_still() {
+            write!(f, "still since gen {}", self.since)
+        } else if self.is_spaceship() {
+            write!(f, "spaceship period {} ({:+}, {:+}) since gen {}", self.period, self.dx, self.dy, self.since)
+        } else {
+            write!(f, "period {} since gen {}", self.period, self.since)
+        }
+    }
+}
+
+// FNV-1a 64 bits.
+fn fnv(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |h, &b| (h ^ b as u64).wrapping_mul(0x100_0000_01b3))
+}
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+pub fn hash_grid(grid: &Grid, state: &[u8]) -> u64 {
+    fnv(fnv(FNV_OFFSET, grid.as_slice()), state)
+}
+
+// Hachage de la zone non nulle recadrée, et son coin haut-gauche. None si grille vide.
+pub fn hash_shape(grid: &Grid, state: &[u8]) -> Option<(u64, usize, usize)> {
+    let w = grid.width();
+    let cells = grid.as_slice();
+    let (mut x0, mut y0, mut x1, mut y1) = (usize::MAX, usize::MAX, 0, 0);
+    for (i, _) in cells.iter().enumerate().filter(|(_, v)| **v != 0) {
+        let (x, y) = (i % w, i / w);
+        x0 = x0.min(x);
+        y0 = y0.min(y);
+        x1 = x1.max(x);
+        y1 = y1.max(y);
+    }
+    if x0 == usize::MAX {
+        return None;
+    }
+
+    let mut h = fnv(FNV_OFFSET, &((x1 - x0) as u64).to_le_bytes());
+    for y in y0..=y1 {
+        h = fnv(h, &cells[y * w + x0..=y * w + x1]);
+    }
+    Some((fnv(h, state), x0, y0))
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CycleDetector {
+    // hachage -> génération
+    seen: HashMap<u64, u64>,
+    // hachage de forme -> (génération, coin haut-gauche)
+    shapes: HashMap<u64, (u64, usize, usize)>,
+    // hachages ajoutés à chaque observation, du plus ancien au plus récent
+    order: VecDeque<(Option<u64>, Option<u64>)>,
+    found: Option<Cycle>,
+}
+
+impl CycleDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clear(&mut self) {
+        self.seen.clear();
+        self.shapes.clear();
+        self.order.clear();
+        self.found = None;
+    }
+
+    // Premier cycle trouvé depuis le dernier `clear`.
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.found
+    }
+
+    pub fn observe(&mut self, generation: u64, grid: &Grid, state: &[u8]) -> Option<Cycle> {
+        if self.found.is_some() {
+            return self.found;
+        }
+
+        let mut added = (None, None);
+        let h = hash_grid(grid, state);
+        if let Some(&g0) = self.seen.get(&h) {
+            if generation > g0 {
+                self.found = Some(Cycle { period: generation - g0, since: g0, dx: 0, dy: 0 });
+                return self.found;
+            }
+        } else {
+            self.seen.insert(h, generation);
+            added.0 = Some(h);
+        }
+
+        if let Some((s, x, y)) = hash_shape(grid, state) {
+            match self.shapes.get(&s) {
+                Some(&(g0, x0, y0)) if generation > g0 && (x, y) != (x0, y0) => {
+                    self.found = Some(Cycle {
+                        period: generation - g0,
+                        since: g0,
+                        dx: x as isize - x0 as isize,
+                        dy: y as isize - y0 as isize,
+                    });
+                }
+                Some(_) => {}
+                None => {
+                    self.shapes.insert(s, (generation, x, y));
+                    added.1 = Some(s);
+                }
+            }
+        }
+
+        if added != (None, None) {
+            self.order.push_back(added);
+        }
+        if self.order.len() > MAX_SEEN
+            && let Some((h, s)) = self.order.pop_front() {
+            if let Some(h) = h {
+                self.seen.remove(&h);
+            }
+            if let Some(s) = s {
+                self.shapes.remove(&s);
+            }
+        }
+        self.found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_is_bounded() {
+        let mut detector = CycleDetector::new();
+        let g = Grid::new(4, 4);
+        for generation in 0..MAX_SEEN as u64 + 100 {
+            assert_eq!(detector.observe(generation, &g, &generation.to_le_bytes()), None);
+        }
+        assert_eq!(detector.seen.len(), MAX_SEEN);
+        assert!(detector.shapes.len() <= MAX_SEEN);
+
+        // une configuration récente revient : cycle trouvé
+        let last = MAX_SEEN as u64 + 99;
+        let period = detector.observe(last + 1, &g, &last.to_le_bytes()).map(|c| c.period);
+        assert_eq!(period, Some(1));
+    }
+}
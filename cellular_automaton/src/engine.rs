@@ -1,45 +1,565 @@
-use crate::{automaton::Automaton, grid::Grid};
+use std::collections::VecDeque;
+
+use crate::{automaton::Automaton, cycle::{Cycle, CycleDetector}, grid::{Anchor, Grid}, reversible, scheme::{Scheduler, UpdateScheme}, seeding::Seeding, stats::Stats};
+
+// Nombre de pas conservés pour pouvoir reculer.
+const HISTORY_LEN: usize = 256;
+
+// Condition de fin d'une étape de scénario.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Until {
+    // un pas n'a changé ni la grille ni l'état de l'automate, ou au plus tard après n pas
+    // (certaines règles n'atteignent jamais un point fixe)
+    StableOr(u64),
+    // nombre de pas depuis le début de l'étape
+    Steps(u64),
+    // l'automate signale qu'il a terminé (`is_finished`), ou au plus tard après n pas
+    FinishedOr(u64),
+}
+
+// Façon de redimensionner la grille.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Resize {
+    // garde le contenu autour de l'ancre : recadre ou complète par des 0
+    Keep(Anchor),
+    // rééchantillonne le contenu (plus proche voisin)
+    Scale,
+    // relance `init` à la nouvelle taille
+    Init,
+}
+
+impl Resize {
+    pub const ALL: [Resize; 4] = [Resize::Keep(Anchor::TopLeft), Resize::Keep(Anchor::Center), Resize::Scale, Resize::Init];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Resize::Keep(Anchor::TopLeft) => "crop/pad top-left",
+            Resize::Keep(Anchor::Center) => "crop/pad centre",
+            Resize::Scale => "scale",
+            Resize::Init => "re-init",
+        }
+    }
+}
+
+// Grille trop petite pour un automate (voir `Automaton::min_size`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct SizeError {
+    pub automaton: String,
+    pub min: (usize, usize),
+    pub size: (usize, usize),
+}
+
+impl std::fmt::Display for SizeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: grille {}×{} trop petite (minimum {}×{})",
+            self.automaton, self.size.0, self.size.1, self.min.0, self.min.1)
+    }
+}
+
+pub fn check_size(automaton: &dyn Automaton, w: usize, h: usize) -> Result<(), SizeError> {
+    let (mw, mh) = automaton.min_size();
+    if w >= mw && h >= mh {
+        Ok(())
+    } else {
+        Err(SizeError { automaton: automaton.name().to_string(), min: (mw, mh), size: (w, h) })
+    }
+}
+
+// Scénario en cours : l'automate courant tourne jusqu'à `until`, puis la grille passe à `then`.
+struct Handoff {
+    until: Until,
+    then: Box<dyn Automaton>,
+    steps: u64,
+}
 
 pub struct Engine {
     current: Grid,
     next: Grid,
     automaton: Box<dyn Automaton>,
-    async_fact: f32,
+    scheduler: Scheduler,
+
+    // (grille, état de l'automate) avant chaque pas, le plus récent à la fin
+    history: VecDeque<(Grid, Box<dyn Automaton>)>,
+
+    handoff: Option<Handoff>,
+
+    // pas depuis l'`init` (ou le passage de relais), et celui où l'automate a terminé
+    generation: u64,
+    finished: Option<u64>,
+
+    // une mesure par génération, depuis le dernier `reset`/`set_grid`
+    stats: Stats,
+    // configurations déjà vues, depuis le dernier changement hors pas (reset, peinture, recul...)
+    cycles: CycleDetector,
+
+    // condition initiale à la place (ou par-dessus) de l'`init` de l'automate
+    seeding: Option<Seeding>,
+
+    // mode réversible du second ordre (voir `reversible`) : grille précédente, à 0 au départ.
+    // L'historique n'est alors pas utilisé : on recule par le calcul, en mémoire constante.
+    previous: Option<Grid>,
 }
 
 impl Engine {
-    pub fn new(w: usize, h: usize, async_fact: f32, automaton: Box<dyn Automaton>) -> Self {
-        let mut current = Grid::new(w, h);
-        let next = Grid::new(w, h);
+    // La grille est agrandie au besoin jusqu'à `min_size` de l'automate.
+    pub fn new(w: usize, h: usize, scheme: UpdateScheme, mut automaton: Box<dyn Automaton>) -> Self {
+        let (mw, mh) = automaton.min_size();
+        let mut current = Grid::new(w.max(mw), h.max(mh));
         automaton.init(&mut current);
-        Self { current, next, automaton, async_fact }
+        Self::build(current, scheme, automaton)
+    }
+
+    // Part d'une grille telle quelle, sans `init` (comme `restore`).
+    pub fn with_grid(grid: Grid, scheme: UpdateScheme, automaton: Box<dyn Automaton>) -> Result<Self, SizeError> {
+        check_size(automaton.as_ref(), grid.width(), grid.height())?;
+        Ok(Self::build(grid, scheme, automaton))
+    }
+
+    fn build(current: Grid, scheme: UpdateScheme, automaton: Box<dyn Automaton>) -> Self {
+        let next = Grid::new(current.width(), current.height());
+        let mut stats = Stats::new();
+        stats.record(0, None, &current, automaton.as_ref());
+        let mut cycles = CycleDetector::new();
+        cycles.observe(0, &current, &automaton.save_state());
+        Self { current, next, automaton, scheduler: Scheduler::new(scheme), history: VecDeque::new(), handoff: None, generation: 0, finished: None, stats, cycles, seeding: None, previous: None }
     }
 
     pub fn current(&self) -> &Grid {
         &self.current
     }
 
-    pub fn set_automaton(&mut self, automaton: Box<dyn Automaton>) {
+    pub fn automaton(&self) -> &dyn Automaton {
+        self.automaton.as_ref()
+    }
+
+    pub fn set_automaton(&mut self, automaton: Box<dyn Automaton>) -> Result<(), SizeError> {
+        check_size(automaton.as_ref(), self.current.width(), self.current.height())?;
         self.automaton = automaton;
-        self.automaton.init(&mut self.current);
+        self.handoff = None;
+        if !self.automaton.is_local() {
+            self.previous = None;
+        }
+        self.reset();
+        Ok(())
+    }
+
+    // Lance `first` depuis son `init`, puis quand `until` est atteint, passe la grille telle
+    // quelle à `then` (via son `soft_init`, ex: placement départ/arrivée du solveur).
+    pub fn chain(&mut self, first: Box<dyn Automaton>, until: Until, then: Box<dyn Automaton>) -> Result<(), SizeError> {
+        check_size(then.as_ref(), self.current.width(), self.current.height())?;
+        self.set_automaton(first)?;
+        self.handoff = Some(Handoff { until, then, steps: 0 });
+        Ok(())
+    }
+
+    // Nom de l'automate qui prendra la suite, si un scénario est en cours.
+    pub fn pending(&self) -> Option<&str> {
+        self.handoff.as_ref().map(|h| h.then.name())
+    }
+
+    // Relance l'automate courant depuis son `init` (ou la graine choisie).
+    pub fn reset(&mut self) {
+        self.automaton.reset();
+        match &self.seeding {
+            Some(s) => s.apply(self.automaton.as_mut(), &mut self.current),
+            None => self.automaton.init(&mut self.current),
+        }
         self.next = Grid::new(self.current.width(), self.current.height());
+        self.clear_previous();
+        self.scheduler.reset();
+        self.history.clear();
+        self.generation = 0;
+        self.finished = None;
+        self.stats.clear();
+        self.stats.record(0, None, &self.current, self.automaton.as_ref());
+        self.forget_cycles();
+    }
+
+    // Change la taille de la grille. Hors `Init`, l'automate est remis à zéro puis
+    // `soft_init` sur le contenu conservé (positions propres à l'automate invalides).
+    pub fn resize(&mut self, w: usize, h: usize, mode: Resize) -> Result<(), SizeError> {
+        let (w, h) = (w.max(1), h.max(1));
+        check_size(self.automaton.as_ref(), w, h)?;
+        if let Some(then) = &self.handoff {
+            check_size(then.then.as_ref(), w, h)?;
+        }
+        match mode {
+            Resize::Init => {
+                self.current = Grid::new(w, h);
+                self.reset();
+            }
+            Resize::Keep(anchor) => self.resize_with(self.current.resized(w, h, anchor)),
+            Resize::Scale => self.resize_with(self.current.scaled(w, h)),
+        }
+        Ok(())
+    }
+
+    fn resize_with(&mut self, grid: Grid) {
+        self.automaton.reset();
+        self.replace_grid(grid);
+        self.automaton.soft_init(&mut self.current);
+    }
+
+    // Reprend une exécution sauvegardée : grille et automate déjà dans leur état, sans `init`.
+    pub fn restore(&mut self, grid: Grid, automaton: Box<dyn Automaton>) -> Result<(), SizeError> {
+        check_size(automaton.as_ref(), grid.width(), grid.height())?;
+        self.automaton = automaton;
+        self.handoff = None;
+        if !self.automaton.is_local() {
+            self.previous = None;
+        }
+        self.replace_grid(grid);
+        Ok(())
+    }
+
+    pub fn seeding(&self) -> Option<&Seeding> {
+        self.seeding.as_ref()
+    }
+
+    // Change la condition initiale et relance (None : `init` de l'automate).
+    pub fn set_seeding(&mut self, seeding: Option<Seeding>) {
+        self.seeding = seeding;
+        self.reset();
+    }
+
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    // Cycle détecté : point fixe, oscillateur ou vaisseau, avec sa période.
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycles.cycle()
+    }
+
+    // Repart de zéro pour la détection de cycles, depuis la grille courante.
+    fn forget_cycles(&mut self) {
+        self.cycles.clear();
+        self.cycles.observe(self.generation, &self.current, &self.cycle_state());
+    }
+
+    // État hors grille qui détermine la suite : celui de l'automate, et la grille précédente
+    // en mode second ordre.
+    fn cycle_state(&self) -> Vec<u8> {
+        let mut state = self.automaton.save_state();
+        if let Some(p) = &self.previous {
+            state.extend_from_slice(p.as_slice());
+        }
+        state
+    }
+
+    // Passe en mode réversible du second ordre (grille précédente à 0), ou en sort.
+    // Faux si l'automate n'est pas local (pas de règle f à envelopper).
+    pub fn set_second_order(&mut self, on: bool) -> bool {
+        if on && !self.automaton.is_local() {
+            return false;
+        }
+        self.previous = on.then(|| Grid::new(self.current.width(), self.current.height()));
+        self.history.clear();
+        self.finished = None;
+        self.forget_cycles();
+        true
+    }
+
+    pub fn is_second_order(&self) -> bool {
+        self.previous.is_some()
+    }
+
+    // Grille précédente du mode second ordre.
+    pub fn previous(&self) -> Option<&Grid> {
+        self.previous.as_ref()
+    }
+
+    // Remplace la grille précédente du mode second ordre (même taille que la courante).
+    // Faux hors mode second ordre ou si la taille diffère.
+    pub fn set_previous(&mut self, grid: Grid) -> bool {
+        let fits = grid.width() == self.current.width() && grid.height() == self.current.height();
+        match &mut self.previous {
+            Some(p) if fits => *p = grid,
+            _ => return false,
+        }
+        self.forget_cycles();
+        true
+    }
+
+    fn clear_previous(&mut self) {
+        if let Some(p) = &mut self.previous {
+            *p = Grid::new(self.current.width(), self.current.height());
+        }
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    // Génération à laquelle l'automate a signalé sa fin. L'engine est alors en pause :
+    // `step_once` ne fait plus rien jusqu'au prochain `reset`, `set_grid` ou `step_back`.
+    pub fn finished(&self) -> Option<u64> {
+        self.finished
     }
 
     pub fn step_once(&mut self) {
-        self.automaton.step(&self.current, &mut self.next, self.async_fact);
+        if self.finished.is_some() {
+            return;
+        }
+        if self.previous.is_some() {
+            self.step_second_order();
+            return;
+        }
+
+        if self.history.len() == HISTORY_LEN {
+            self.history.pop_front();
+        }
+        self.history.push_back((self.current.clone(), self.automaton.clone()));
+
+        if self.automaton.is_local() {
+            self.scheduler.apply(self.automaton.as_ref(), &mut self.current, &mut self.next);
+        } else {
+            self.automaton.step(&self.current, &mut self.next);
+            self.current.swap(&mut self.next);
+        }
+        self.generation += 1;
+
+        if self.handoff_due() {
+            let h = self.handoff.take().expect("scénario en cours");
+            self.automaton = h.then;
+            self.automaton.soft_init(&mut self.current);
+            self.scheduler.reset();
+            self.generation = 0;
+            self.cycles.clear();
+            // nouvelle exécution : mesures et historique ne mélangent pas les deux automates,
+            // et on ne recule pas jusque dans le premier
+            self.stats.clear();
+            self.history.clear();
+        } else if self.automaton.is_finished(&self.current) {
+            self.finished = Some(self.generation);
+        }
+
+        let prev = self.history.back().map(|(g, _)| g);
+        self.stats.record(self.generation, prev, &self.current, self.automaton.as_ref());
+        // les schémas aléatoires peuvent revisiter une configuration sans être périodiques
+        if !self.automaton.is_local() || self.scheme() == UpdateScheme::Synchronous {
+            self.cycles.observe(self.generation, &self.current, &self.cycle_state());
+        }
+    }
+
+    // (previous, current) <- (current, f(current) - previous). Pas de fin ni de relais :
+    // `is_finished` et les scénarios portent sur la règle f seule.
+    fn step_second_order(&mut self) {
+        let Some(prev) = &mut self.previous else { return };
+        reversible::second_order(self.automaton.as_ref(), prev, &self.current, &mut self.next);
+        std::mem::swap(prev, &mut self.current);
         self.current.swap(&mut self.next);
+        self.generation += 1;
+        self.stats.record(self.generation, self.previous.as_ref(), &self.current, self.automaton.as_ref());
+        self.cycles.observe(self.generation, &self.current, &self.cycle_state());
+    }
+
+    fn handoff_due(&mut self) -> bool {
+        let Some(h) = &mut self.handoff else { return false };
+        h.steps += 1;
+        match h.until {
+            Until::Steps(n) => h.steps >= n,
+            Until::FinishedOr(n) => h.steps >= n || self.automaton.is_finished(&self.current),
+            Until::StableOr(n) => h.steps >= n || self.history.back().is_some_and(|(grid, automaton)| {
+                grid.as_slice() == self.current.as_slice()
+                    && automaton.save_state() == self.automaton.save_state()
+            }),
+        }
+    }
+
+    // Recule d'un pas : par le calcul en mode second ordre, sinon depuis l'historique, sinon
+    // par inversion si l'automate est réversible.
+    pub fn step_back(&mut self) -> bool {
+        if let Some(prev) = &mut self.previous {
+            // (previous, current) <- (f(previous) - current, previous)
+            reversible::second_order(self.automaton.as_ref(), &self.current, prev, &mut self.next);
+            std::mem::swap(prev, &mut self.current);
+            std::mem::swap(prev, &mut self.next);
+        } else if let Some((grid, automaton)) = self.history.pop_back() {
+            self.current = grid;
+            self.automaton = automaton;
+        } else if self.automaton.is_reversible() {
+            self.automaton.step_back(&self.current, &mut self.next);
+            self.current.swap(&mut self.next);
+        } else {
+            return false;
+        }
+        self.generation = self.generation.saturating_sub(1);
+        self.finished = None;
+        self.stats.pop();
+        if self.stats.last().is_none() {
+            self.stats.record(self.generation, None, &self.current, self.automaton.as_ref());
+        }
+        self.forget_cycles();
+        true
+    }
+
+    pub fn can_step_back(&self) -> bool {
+        !self.history.is_empty() || self.is_reversible()
+    }
+
+    pub fn is_reversible(&self) -> bool {
+        self.previous.is_some() || self.automaton.is_reversible()
+    }
+
+    // Pose une valeur à la main (peinture). Relance l'automate s'il avait terminé.
+    pub fn paint(&mut self, x: usize, y: usize, value: u8) {
+        if self.current.try_set(x, y, value) {
+            self.edited();
+        }
+    }
+
+    // Colle un fragment au coin (x, y), en tore (voir `Grid::stamp`).
+    pub fn stamp(&mut self, fragment: &Grid, x: usize, y: usize, transparent: bool) {
+        self.current.stamp(fragment, x, y, transparent);
+        self.edited();
+    }
+
+    // Après une modification à la main : l'automate repart, mesures et cycles à jour.
+    fn edited(&mut self) {
+        self.finished = None;
+        self.stats.pop();
+        self.stats.record(self.generation, None, &self.current, self.automaton.as_ref());
+        self.forget_cycles();
     }
 
     pub fn soft_init(&mut self) {
         self.automaton.soft_init(&mut self.current);
     }
 
-    pub fn set_grid(&mut self, grid: Grid) {
+    // Remplace la grille, en adoptant sa taille si l'automate (et celui qui prendra la
+    // suite) le permet.
+    pub fn set_grid(&mut self, grid: Grid) -> Result<(), SizeError> {
+        check_size(self.automaton.as_ref(), grid.width(), grid.height())?;
+        if let Some(h) = &self.handoff {
+            check_size(h.then.as_ref(), grid.width(), grid.height())?;
+        }
+        self.replace_grid(grid);
+        Ok(())
+    }
+
+    fn replace_grid(&mut self, grid: Grid) {
         self.current = grid;
         self.next = Grid::new(self.current.width(), self.current.height());
+        self.clear_previous();
+        self.scheduler.reset();
+        self.history.clear();
+        self.generation = 0;
+        self.finished = None;
+        self.stats.clear();
+        self.stats.record(0, None, &self.current, self.automaton.as_ref());
+        self.forget_cycles();
+    }
+
+    pub fn scheme(&self) -> UpdateScheme {
+        self.scheduler.scheme()
+    }
+
+    // Les configurations vues sous l'ancien schéma ne disent rien de la suite.
+    pub fn set_scheme(&mut self, scheme: UpdateScheme) {
+        self.scheduler.set_scheme(scheme);
+        self.forget_cycles();
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        self.scheduler.set_seed(seed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automata::{mazegenerator::MazeGenerator, mazesolver::MazeSolver};
+
+    #[test]
+    fn handoff_starts_a_new_run() {
+        let generator = MazeGenerator::new().with_seed(3);
+        let mut engine = Engine::new(21, 15, UpdateScheme::Synchronous, Box::new(generator.clone()));
+        engine.chain(Box::new(generator), Until::FinishedOr(u64::MAX), Box::new(MazeSolver::new())).expect("taille suffisante");
+        while engine.pending().is_some() {
+            engine.step_once();
+        }
+
+        assert_eq!(engine.generation(), 0);
+        assert!(engine.automaton().name().starts_with("mazesolver"));
+        let generations: Vec<u64> = engine.stats().samples().iter().map(|s| s.generation).collect();
+        assert_eq!(generations, vec![0]);
+        assert!(!engine.can_step_back());
+        assert!(!engine.step_back());
+
+        engine.step_once();
+        engine.step_once();
+        assert!(engine.step_back());
+        assert_eq!(engine.generation(), 1);
+        assert!(engine.automaton().name().starts_with("mazesolver"));
+        let generations: Vec<u64> = engine.stats().samples().iter().map(|s| s.generation).collect();
+        assert_eq!(generations, vec![0, 1]);
+    }
+
+    #[test]
+    fn resize_modes() {
+        let life = crate::automata::by_name("life").expect("automate");
+        let mut engine = Engine::new(4, 4, UpdateScheme::Synchronous, life);
+        let mut g = Grid::new(4, 4);
+        g.set(1, 1, 1);
+        g.set(2, 1, 1);
+        engine.set_grid(g.clone()).expect("taille suffisante");
+
+        engine.resize(6, 6, Resize::Keep(Anchor::Center)).expect("taille suffisante");
+        let padded = g.resized(6, 6, Anchor::Center);
+        assert_eq!(engine.current().as_slice(), padded.as_slice());
+        assert_eq!(engine.generation(), 0);
+        engine.resize(3, 3, Resize::Scale).expect("taille suffisante");
+        assert_eq!(engine.current().as_slice(), padded.scaled(3, 3).as_slice());
+        engine.resize(0, 3, Resize::Init).expect("taille suffisante");
+        assert_eq!((engine.current().width(), engine.current().height()), (1, 3));
+    }
+
+    #[test]
+    fn resize_below_min_size() {
+        let wireworld = crate::automata::by_name("wireworld").expect("automate");
+        let (w, h) = wireworld.min_size();
+        let mut engine = Engine::new(w, h, UpdateScheme::Synchronous, wireworld);
+        let before = engine.current().clone();
+        for mode in [Resize::Init, Resize::Scale, Resize::Keep(Anchor::TopLeft)] {
+            assert!(engine.resize(w - 1, h, mode).is_err());
+            assert!(engine.resize(w, h - 1, mode).is_err());
+        }
+        assert_eq!(engine.current().as_slice(), before.as_slice());
     }
 
-    pub fn set_async_fact(&mut self, async_fact: f32) {
-        self.async_fact = async_fact;
+    #[test]
+    fn grids_below_min_size_are_refused() {
+        for automaton in crate::automata::available() {
+            let (w, h) = automaton.min_size();
+            assert!(check_size(automaton.as_ref(), w, h).is_ok(), "{}", automaton.name());
+            if w > 1 {
+                assert!(check_size(automaton.as_ref(), w - 1, h).is_err(), "{}", automaton.name());
+            }
+            if h > 1 {
+                let err = check_size(automaton.as_ref(), w, h - 1).expect_err("trop petite");
+                assert_eq!((err.min, err.size), ((w, h), (w, h - 1)));
+            }
+        }
+
+        let wireworld = crate::automata::by_name("wireworld").expect("automate");
+        let (w, h) = wireworld.min_size();
+        // `new` agrandit jusqu'au minimum, `set_grid` et `with_grid` refusent
+        let mut engine = Engine::new(1, 1, UpdateScheme::Synchronous, wireworld.box_clone());
+        assert_eq!((engine.current().width(), engine.current().height()), (w, h));
+        assert!(engine.set_grid(Grid::new(w, h - 1)).is_err());
+        assert!(Engine::with_grid(Grid::new(w - 1, h), UpdateScheme::Synchronous, wireworld).is_err());
+    }
+
+    #[test]
+    fn scheme_change_forgets_cycles() {
+        let life = crate::automata::by_name("life").expect("automate");
+        let mut engine = Engine::new(8, 8, UpdateScheme::Synchronous, life);
+        engine.set_grid(Grid::new(8, 8)).expect("taille suffisante");
+        engine.step_once();
+        assert!(engine.cycle().is_some_and(|c| c.is_still()));
+
+        engine.set_scheme(UpdateScheme::FullyAsync);
+        assert_eq!(engine.cycle(), None);
     }
 }
@@ -1,3 +1,20 @@
+// Point fixe d'un redimensionnement : le contenu garde ce coin (ou ce centre) en place.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Anchor {
+    TopLeft,
+    Center,
+}
+
+// 8 symétries du carré sur des coordonnées (x, y) : identité, rotations de 90°, 180°, 270°,
+// puis miroir vertical composé avec ces rotations.
+pub type Transform = fn(i32, i32) -> (i32, i32);
+pub const D4: [Transform; 8] = [
+    |x, y| (x, y), |x, y| (-y, x), |x, y| (-x, -y), |x, y| (y, -x),
+    |x, y| (-x, y), |x, y| (y, x), |x, y| (x, -y), |x, y| (-y, -x),
+];
+pub const ROTATE: Transform = D4[1];
+pub const FLIP: Transform = D4[4];
+
 #[derive(Clone)]
 pub struct Grid {
     w: usize,
@@ -35,6 +52,27 @@ impl Grid {
         self.cells[i] = value;
     }
 
+    // Accès vérifiés : None / faux hors de la grille.
+    pub fn try_get(&self, x: usize, y: usize) -> Option<u8> {
+        (x < self.w && y < self.h).then(|| self.cells[y * self.w + x])
+    }
+
+    pub fn try_set(&mut self, x: usize, y: usize, value: u8) -> bool {
+        if x < self.w && y < self.h {
+            self.cells[y * self.w + x] = value;
+            true
+        } else {
+            false
+        }
+    }
+
+    // Accès en tore : les coordonnées sont ramenées dans la grille.
+    pub fn get_wrapped(&self, x: isize, y: isize) -> u8 {
+        let x = x.rem_euclid(self.w as isize) as usize;
+        let y = y.rem_euclid(self.h as isize) as usize;
+        self.cells[y * self.w + x]
+    }
+
     pub fn fill(&mut self, value: u8) {
         self.cells.fill(value);
     }
@@ -54,4 +92,165 @@ impl Grid {
     pub fn as_mut_slice(&mut self) -> &mut [u8] { 
         &mut self.cells 
     }
+
+    // Copie dans une grille w×h : recadre ce qui dépasse, complète par des 0.
+    pub fn resized(&self, w: usize, h: usize, anchor: Anchor) -> Grid {
+        let mut out = Grid::new(w, h);
+        // décalage de l'ancien contenu dans la nouvelle grille
+        let (ox, oy) = match anchor {
+            Anchor::TopLeft => (0, 0),
+            Anchor::Center => ((w as isize - self.w as isize) / 2, (h as isize - self.h as isize) / 2),
+        };
+        for y in 0..h {
+            let sy = y as isize - oy;
+            if sy < 0 || sy >= self.h as isize { continue; }
+            for x in 0..w {
+                let sx = x as isize - ox;
+                if sx < 0 || sx >= self.w as isize { continue; }
+                out.set(x, y, self.get(sx as usize, sy as usize));
+            }
+        }
+        out
+    }
+
+    // Copie de la zone w×h au coin (x, y), en tore.
+    pub fn region(&self, x: usize, y: usize, w: usize, h: usize) -> Grid {
+        let mut out = Grid::new(w, h);
+        for j in 0..h {
+            for i in 0..w {
+                out.set(i, j, self.get_wrapped((x + i) as isize, (y + j) as isize));
+            }
+        }
+        out
+    }
+
+    // Pose `src` au coin (x, y), en tore. `transparent` : les 0 de `src` ne recouvrent rien.
+    pub fn stamp(&mut self, src: &Grid, x: usize, y: usize, transparent: bool) {
+        for j in 0..src.h {
+            for i in 0..src.w {
+                let v = src.get(i, j);
+                if transparent && v == 0 { continue; }
+                let gx = (x + i) % self.w;
+                let gy = (y + j) % self.h;
+                self.set(gx, gy, v);
+            }
+        }
+    }
+
+    // Image de la grille par une symétrie de `D4`, recalée en (0, 0).
+    pub fn transformed(&self, t: Transform) -> Grid {
+        let (cw, ch) = t(self.w as i32 - 1, self.h as i32 - 1);
+        let (ox, oy) = (cw.min(0), ch.min(0));
+        let (w, h) = (cw.unsigned_abs() as usize + 1, ch.unsigned_abs() as usize + 1);
+        let mut out = Grid::new(w, h);
+        for y in 0..self.h {
+            for x in 0..self.w {
+                let (tx, ty) = t(x as i32, y as i32);
+                out.set((tx - ox) as usize, (ty - oy) as usize, self.get(x, y));
+            }
+        }
+        out
+    }
+
+    // Rééchantillonne en w×h, au plus proche voisin.
+    pub fn scaled(&self, w: usize, h: usize) -> Grid {
+        let mut out = Grid::new(w, h);
+        for y in 0..h {
+            for x in 0..w {
+                out.set(x, y, self.get(x * self.w / w, y * self.h / h));
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Grille w×h depuis ses cases, rangée par rangée.
+    fn grid(w: usize, h: usize, cells: &[u8]) -> Grid {
+        assert_eq!(cells.len(), w * h);
+        Grid { w, h, cells: cells.to_vec() }
+    }
+
+    #[test]
+    fn checked_and_wrapped_access() {
+        let mut g = grid(3, 2, &[1, 2, 3, 4, 5, 6]);
+        assert_eq!(g.try_get(2, 1), Some(6));
+        assert_eq!(g.try_get(3, 0), None);
+        assert_eq!(g.try_get(0, 2), None);
+        assert_eq!(g.try_get(usize::MAX, 0), None);
+        assert!(!g.try_set(3, 1, 9));
+        assert!(!g.try_set(0, 2, 9));
+        assert_eq!(g.as_slice(), &[1, 2, 3, 4, 5, 6]);
+        assert!(g.try_set(0, 1, 9));
+        assert_eq!(g.get(0, 1), 9);
+
+        assert_eq!(g.get_wrapped(-1, 0), 3);
+        assert_eq!(g.get_wrapped(3, -1), 9);
+        assert_eq!(g.get_wrapped(-4, 5), 6);
+    }
+
+    #[test]
+    fn resized_keeps_the_anchor() {
+        let g = grid(3, 2, &[1, 2, 3, 4, 5, 6]);
+        assert_eq!(g.resized(4, 3, Anchor::TopLeft).as_slice(), &[1, 2, 3, 0, 4, 5, 6, 0, 0, 0, 0, 0]);
+        assert_eq!(g.resized(2, 1, Anchor::TopLeft).as_slice(), &[1, 2]);
+        assert_eq!(g.resized(5, 4, Anchor::Center).as_slice(), &[
+            0, 0, 0, 0, 0,
+            0, 1, 2, 3, 0,
+            0, 4, 5, 6, 0,
+            0, 0, 0, 0, 0,
+        ]);
+        assert_eq!(g.resized(1, 1, Anchor::Center).as_slice(), &[2]);
+        assert_eq!(g.resized(3, 2, Anchor::Center).as_slice(), g.as_slice());
+    }
+
+    #[test]
+    fn scaled_nearest_neighbour() {
+        let g = grid(2, 2, &[1, 2, 3, 4]);
+        let big = g.scaled(4, 4);
+        assert_eq!(big.as_slice(), &[1, 1, 2, 2, 1, 1, 2, 2, 3, 3, 4, 4, 3, 3, 4, 4]);
+        assert_eq!(big.scaled(2, 2).as_slice(), g.as_slice());
+        assert_eq!(g.scaled(3, 1).as_slice(), &[1, 1, 2]);
+    }
+
+    #[test]
+    fn d4_round_trips() {
+        let g = grid(3, 2, &[1, 2, 3, 4, 5, 6]);
+        let r = g.transformed(ROTATE);
+        assert_eq!((r.width(), r.height()), (2, 3));
+        let r4 = (0..4).fold(g.clone(), |a, _| a.transformed(ROTATE));
+        assert_eq!((r4.width(), r4.height()), (3, 2));
+        assert_eq!(r4.as_slice(), g.as_slice());
+        assert_eq!(g.transformed(FLIP).as_slice(), &[3, 2, 1, 6, 5, 4]);
+        assert_eq!(g.transformed(FLIP).transformed(FLIP).as_slice(), g.as_slice());
+
+        // 8 images distinctes pour une grille sans symétrie
+        let mut images: Vec<Vec<u8>> = D4.iter().map(|&t| {
+            let i = g.transformed(t);
+            [vec![i.width() as u8], i.as_slice().to_vec()].concat()
+        }).collect();
+        images.sort();
+        images.dedup();
+        assert_eq!(images.len(), 8);
+    }
+
+    #[test]
+    fn region_and_stamp_wrap() {
+        let g = grid(3, 2, &[1, 2, 3, 4, 5, 6]);
+        assert_eq!(g.region(2, 1, 2, 2).as_slice(), &[6, 4, 3, 1]);
+
+        let mut dst = grid(3, 2, &[7, 7, 7, 7, 7, 7]);
+        dst.stamp(&grid(2, 1, &[0, 9]), 2, 1, true);
+        assert_eq!(dst.as_slice(), &[7, 7, 7, 9, 7, 7]);
+        dst.stamp(&grid(2, 1, &[0, 9]), 2, 1, false);
+        assert_eq!(dst.as_slice(), &[7, 7, 7, 9, 7, 0]);
+
+        // une copie reposée au même endroit ne change rien
+        let mut copy = g.clone();
+        copy.stamp(&g.region(1, 1, 3, 2), 1, 1, false);
+        assert_eq!(copy.as_slice(), g.as_slice());
+    }
 }
@@ -5,10 +5,11 @@ use std::{
     path::{Path, PathBuf}
 };
 
-use crate::grid::Grid;
+use crate::{automaton::Automaton, grid::Grid};
 
 const MAGIC: [u8; 4] = *b"CAGR";
-const VERSION: u8 = 1;
+// v1 : grille seule. v2 : grille + automate (nom et état) optionnels.
+const VERSION: u8 = 2;
 
 pub const GRIDS_DIR: &str = "grids";
 pub const EXT: &str = "cagr";
@@ -25,10 +26,9 @@ pub fn list_grids() -> Result<Vec<String>, GridIoError> {
     for entry in fs::read_dir(GRIDS_DIR)? {
         let entry = entry?;
         let path = entry.path();
-        if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some(EXT) {
-            if let Some(name) = path.file_name().and_then(|s| s.to_str()) {
-                out.push(name.to_string());
-            }
+        if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some(EXT)
+            && let Some(name) = path.file_name().and_then(|s| s.to_str()) {
+            out.push(name.to_string());
         }
     }
     out.sort();
@@ -46,13 +46,32 @@ pub enum GridIoError {
     BadMagic,
     UnsupportedVersion(u8),
     BadSize,
+    BadName,
+    // une longueur annoncée dépasse la fin du fichier (fichier tronqué ou corrompu)
+    Truncated,
 }
 
 impl From<io::Error> for GridIoError {
     fn from(e: io::Error) -> Self { GridIoError::Io(e) }
 }
 
+// Automate sauvegardé avec la grille : nom (pour `automata::by_name`) et `save_state`.
+pub struct SavedAutomaton {
+    pub name: String,
+    pub state: Vec<u8>,
+}
+
 pub fn save(path: impl AsRef<Path>, grid: &Grid) -> Result<(), GridIoError> {
+    write_file(path, grid, None)
+}
+
+// Sauvegarde une exécution : la grille et l'état de l'automate qui tourne dessus.
+pub fn save_run(path: impl AsRef<Path>, grid: &Grid, automaton: &dyn Automaton) -> Result<(), GridIoError> {
+    let saved = SavedAutomaton { name: automaton.name().to_string(), state: automaton.save_state() };
+    write_file(path, grid, Some(&saved))
+}
+
+fn write_file(path: impl AsRef<Path>, grid: &Grid, automaton: Option<&SavedAutomaton>) -> Result<(), GridIoError> {
     ensure_dir()?;
 
     let mut f = File::create(path)?;
@@ -65,32 +84,113 @@ pub fn save(path: impl AsRef<Path>, grid: &Grid) -> Result<(), GridIoError> {
     f.write_all(&h.to_le_bytes())?;
 
     f.write_all(grid.as_slice())?;
+
+    // nom (u16 LE + octets, 0 = pas d'automate) puis état (u32 LE + octets)
+    let (name, state) = match automaton {
+        Some(a) => (a.name.as_bytes(), a.state.as_slice()),
+        None => (&[][..], &[][..]),
+    };
+    let name_len = u16::try_from(name.len()).map_err(|_| GridIoError::BadName)?;
+    f.write_all(&name_len.to_le_bytes())?;
+    f.write_all(name)?;
+    f.write_all(&(state.len() as u32).to_le_bytes())?;
+    f.write_all(state)?;
     Ok(())
 }
 
 pub fn load(path: impl AsRef<Path>) -> Result<Grid, GridIoError> {
+    load_run(path).map(|(g, _)| g)
+}
+
+// Charge une grille et, si le fichier en contient un (v2), l'automate sauvegardé avec.
+pub fn load_run(path: impl AsRef<Path>) -> Result<(Grid, Option<SavedAutomaton>), GridIoError> {
     ensure_dir()?;
 
     let mut f = File::open(path)?;
+    // octets restant à lire : aucune longueur lue dans le fichier ne peut les dépasser
+    let mut left = f.metadata()?.len();
+    let mut take = |f: &mut File, n: u64| -> Result<Vec<u8>, GridIoError> {
+        if n > left { return Err(GridIoError::Truncated); }
+        left -= n;
+        let mut buf = vec![0u8; n as usize];
+        f.read_exact(&mut buf)?;
+        Ok(buf)
+    };
 
-    let mut magic = [0u8; 4];
-    f.read_exact(&mut magic)?;
-    if magic != MAGIC { return Err(GridIoError::BadMagic); }
+    let u32_at = |b: Vec<u8>| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as u64;
 
-    let mut ver = [0u8; 1];
-    f.read_exact(&mut ver)?;
-    if ver[0] != VERSION { return Err(GridIoError::UnsupportedVersion(ver[0])); }
+    if take(&mut f, 4)? != MAGIC { return Err(GridIoError::BadMagic); }
 
-    let mut wb = [0u8; 4];
-    let mut hb = [0u8; 4];
-    f.read_exact(&mut wb)?;
-    f.read_exact(&mut hb)?;
-    let w = u32::from_le_bytes(wb) as usize;
-    let h = u32::from_le_bytes(hb) as usize;
+    let ver = take(&mut f, 1)?[0];
+    if ver != 1 && ver != VERSION { return Err(GridIoError::UnsupportedVersion(ver)); }
 
+    let w = u32_at(take(&mut f, 4)?);
+    let h = u32_at(take(&mut f, 4)?);
     if w == 0 || h == 0 { return Err(GridIoError::BadSize); }
 
-    let mut g = Grid::new(w, h);
-    f.read_exact(g.as_mut_slice())?;
-    Ok(g)
+    let cells = take(&mut f, w * h)?;
+    let mut g = Grid::new(w as usize, h as usize);
+    g.as_mut_slice().copy_from_slice(&cells);
+
+    if ver == 1 {
+        return Ok((g, None));
+    }
+
+    let lb = take(&mut f, 2)?;
+    let name = take(&mut f, u16::from_le_bytes([lb[0], lb[1]]) as u64)?;
+    let name = String::from_utf8(name).map_err(|_| GridIoError::BadName)?;
+
+    let state_len = u32_at(take(&mut f, 4)?);
+    let state = take(&mut f, state_len)?;
+
+    let saved = (!name.is_empty()).then_some(SavedAutomaton { name, state });
+    Ok((g, saved))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automata::life::Life;
+
+    fn temp(name: &str, bytes: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("cagr-test-{}-{name}", std::process::id()));
+        fs::write(&path, bytes).expect("fichier temporaire");
+        path
+    }
+
+    #[test]
+    fn run_round_trip() {
+        let mut g = Grid::new(5, 3);
+        g.set(1, 2, 1);
+        let path = std::env::temp_dir().join(format!("cagr-test-{}-run.cagr", std::process::id()));
+        save_run(&path, &g, &Life::conway()).expect("écriture");
+        let (loaded, saved) = load_run(&path).expect("lecture");
+        assert_eq!(loaded.as_slice(), g.as_slice());
+        assert_eq!(saved.map(|s| s.name), Some(Life::conway().name().to_string()));
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn lengths_past_the_end_are_rejected() {
+        // grille annoncée 65536×65536 (4 Gio) dans un fichier de 13 octets
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        bytes.extend(65536u32.to_le_bytes());
+        bytes.extend(65536u32.to_le_bytes());
+        let path = temp("grid", &bytes);
+        assert!(matches!(load_run(&path), Err(GridIoError::Truncated)));
+        let _ = fs::remove_file(path);
+
+        // grille 1×1, nom vide, état annoncé de 4 Gio
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        bytes.extend(1u32.to_le_bytes());
+        bytes.extend(1u32.to_le_bytes());
+        bytes.push(0);
+        bytes.extend(0u16.to_le_bytes());
+        bytes.extend(u32::MAX.to_le_bytes());
+        let path = temp("state", &bytes);
+        assert!(matches!(load_run(&path), Err(GridIoError::Truncated)));
+        let _ = fs::remove_file(path);
+    }
 }
@@ -1 +1,14 @@
-pub mod bin;
\ No newline at end of file
+pub mod bin;
+pub mod png;
+pub mod rle;
+
+use std::io;
+
+// Nom choisi par l'utilisateur pour un fichier de la bibliothèque (fragment, palette) : un
+// simple nom de fichier, sans séparateur, sans `.` en tête (ni `..`), sans caractère de contrôle.
+pub fn check_name(name: &str) -> io::Result<()> {
+    if name.is_empty() || name.starts_with('.') || name.chars().any(|c| matches!(c, '/' | '\\' | ':') || c.is_control()) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("nom invalide: {name:?}")));
+    }
+    Ok(())
+}
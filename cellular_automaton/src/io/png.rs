@@ -0,0 +1,84 @@
+use std::{fs::File, io::{self, Write}, path::Path};
+
+use crate::{automaton::StateInfo, grid::Grid};
+
+// Export PNG sans dépendance : RGB 8 bits, scanlines sans filtre, zlib en blocs non compressés.
+// Chaque cellule fait `scale`×`scale` pixels, couleur prise dans `states` (gris hors schéma).
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+
+fn crc32(chunks: &[&[u8]]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for data in chunks {
+        for &b in *data {
+            crc ^= b as u32;
+            for _ in 0..8 {
+                crc = if crc & 1 != 0 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+            }
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &d in data {
+        a = (a + d as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+fn chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    out.extend_from_slice(&crc32(&[kind, data]).to_be_bytes());
+}
+
+// Flux zlib en blocs "stored" (au plus 65535 octets chacun).
+fn zlib_stored(raw: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = raw.chunks(0xffff).peekable();
+    if blocks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let len = block.len() as u16;
+        out.push(blocks.peek().is_none() as u8);
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+    out.extend_from_slice(&adler32(raw).to_be_bytes());
+    out
+}
+
+pub fn encode(grid: &Grid, states: &[StateInfo], scale: usize) -> Vec<u8> {
+    let scale = scale.max(1);
+    let (w, h) = (grid.width() * scale, grid.height() * scale);
+
+    let mut raw = Vec::with_capacity(h * (1 + 3 * w));
+    for y in 0..h {
+        raw.push(0); // filtre : aucun
+        for x in 0..w {
+            let v = grid.get(x / scale, y / scale) as usize;
+            raw.extend_from_slice(&states.get(v).map_or([128, 128, 128], |s| s.color));
+        }
+    }
+
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend_from_slice(&(w as u32).to_be_bytes());
+    ihdr.extend_from_slice(&(h as u32).to_be_bytes());
+    ihdr.extend_from_slice(&[8, 2, 0, 0, 0]); // 8 bits, RGB, deflate, filtre 0, non entrelacé
+
+    let mut out = SIGNATURE.to_vec();
+    chunk(&mut out, b"IHDR", &ihdr);
+    chunk(&mut out, b"IDAT", &zlib_stored(&raw));
+    chunk(&mut out, b"IEND", &[]);
+    out
+}
+
+pub fn save(path: impl AsRef<Path>, grid: &Grid, states: &[StateInfo], scale: usize) -> io::Result<()> {
+    File::create(path)?.write_all(&encode(grid, states, scale))
+}
@@ -0,0 +1,268 @@
+use std::{fmt, fs, path::{Path, PathBuf}};
+
+use crate::{grid::Grid, io};
+
+// RLE (format de Golly) : en-tête `x = w, y = h[, rule = r]`, puis les rangées séparées par
+// `$` et terminées par `!`, chaque état précédé de sa répétition. Deux états : `b` / `o` ;
+// au-delà : `.` pour 0, `A`..`X` pour 1..24, puis `pA`..`yX` pour 25..255.
+
+pub const STAMPS_DIR: &str = "stamps";
+pub const EXT: &str = "rle";
+
+// Taille maximale d'une grille lue (texte venu du presse-papiers : rien n'est garanti).
+pub const MAX_SIDE: usize = 1 << 14;
+pub const MAX_CELLS: usize = 1 << 24;
+// Sans en-tête, les cellules vivantes sont gardées à part avant de connaître la taille.
+pub const MAX_LOOSE_CELLS: usize = 1 << 16;
+
+#[derive(Debug)]
+pub struct RleError(pub String);
+
+impl fmt::Display for RleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RLE: {}", self.0)
+    }
+}
+
+fn symbol(v: u8, multi: bool) -> String {
+    match (v, multi) {
+        (0, false) => "b".to_string(),
+        (_, false) => "o".to_string(),
+        (0, true) => ".".to_string(),
+        (v, true) => {
+            let (prefix, letter) = ((v - 1) / 24, (b'A' + (v - 1) % 24) as char);
+            match prefix {
+                0 => letter.to_string(),
+                p => format!("{}{letter}", (b'p' + p - 1) as char),
+            }
+        }
+    }
+}
+
+fn push_run(out: &mut String, n: usize, sym: &str) {
+    if n > 1 {
+        out.push_str(&n.to_string());
+    }
+    out.push_str(sym);
+}
+
+pub fn to_rle(grid: &Grid, rule: Option<&str>) -> String {
+    let multi = grid.as_slice().iter().any(|&v| v > 1);
+    let mut out = format!("x = {}, y = {}", grid.width(), grid.height());
+    if let Some(r) = rule {
+        out.push_str(&format!(", rule = {r}"));
+    }
+    out.push('\n');
+
+    let mut body = String::new();
+    let mut empty_rows = 0;
+    for y in 0..grid.height() {
+        let row = &grid.as_slice()[y * grid.width()..(y + 1) * grid.width()];
+        let end = row.iter().rposition(|&v| v != 0).map_or(0, |i| i + 1);
+        if end == 0 {
+            empty_rows += 1;
+            continue;
+        }
+        // rangées vides (et fin de la précédente) en un seul `n$`
+        let rows = empty_rows + !body.is_empty() as usize;
+        if rows > 0 {
+            push_run(&mut body, rows, "$");
+        }
+        empty_rows = 0;
+
+        let mut x = 0;
+        while x < end {
+            let v = row[x];
+            let n = row[x..end].iter().take_while(|&&c| c == v).count();
+            push_run(&mut body, n, &symbol(v, multi));
+            x += n;
+        }
+    }
+    body.push('!');
+
+    // lignes de 70 caractères au plus, sans couper un symbole
+    let mut line = String::new();
+    let mut token = String::new();
+    for c in body.chars() {
+        token.push(c);
+        if c.is_ascii_digit() || ('p'..='y').contains(&c) {
+            continue;
+        }
+        if line.len() + token.len() > 70 {
+            out.push_str(&line);
+            out.push('\n');
+            line.clear();
+        }
+        line.push_str(&token);
+        token.clear();
+    }
+    out.push_str(&line);
+    out.push('\n');
+    out
+}
+
+// Lit une grille RLE. La taille vient de l'en-tête s'il y en a un (les cellules vont alors
+// directement dans la grille), sinon du contenu.
+pub fn from_rle(text: &str) -> Result<Grid, RleError> {
+    let mut size = None;
+    let mut grid: Option<Grid> = None;
+    let mut cells: Vec<(usize, usize, u8)> = Vec::new();
+    let (mut x, mut y, mut w) = (0usize, 0usize, 0usize);
+    let mut count = String::new();
+    let mut prefix = None;
+
+    'lines: for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('x') && line.contains('=') {
+            let field = |k: &str| line.split(',')
+                .filter_map(|f| f.split_once('='))
+                .find(|(a, _)| a.trim() == k)
+                .and_then(|(_, v)| v.trim().parse::<usize>().ok());
+            let (hw, hh) = (field("x").ok_or_else(|| RleError("x invalide".into()))?,
+                            field("y").ok_or_else(|| RleError("y invalide".into()))?);
+            if hw > MAX_SIDE || hh > MAX_SIDE || hw * hh > MAX_CELLS {
+                return Err(RleError(format!("grille {hw}×{hh} trop grande")));
+            }
+            if hw == 0 || hh == 0 {
+                return Err(RleError("grille vide".into()));
+            }
+            size = Some((hw, hh));
+            grid = Some(Grid::new(hw, hh));
+            continue;
+        }
+
+        let (max_w, max_h) = size.unwrap_or((MAX_SIDE, MAX_SIDE));
+        for c in line.chars() {
+            let n = if count.is_empty() || c.is_ascii_digit() { 1 } else {
+                count.parse::<usize>().map_err(|_| RleError(format!("répétition invalide: {count}")))?
+            };
+            let value = match c {
+                '0'..='9' => { count.push(c); continue; }
+                'p'..='y' => { prefix = Some(c as u8 - b'p' + 1); continue; }
+                'b' | '.' => Some(0),
+                'o' => Some(1),
+                'A'..='X' => {
+                    let v = prefix.take().unwrap_or(0) as usize * 24 + (c as u8 - b'A') as usize + 1;
+                    Some(u8::try_from(v).map_err(|_| RleError(format!("état trop grand: {v}")))?)
+                }
+                '$' => {
+                    y = y.checked_add(n).filter(|&y| y <= max_h).ok_or_else(|| RleError(format!("plus de {max_h} rangées")))?;
+                    x = 0;
+                    None
+                }
+                '!' => break 'lines,
+                c if c.is_whitespace() => { continue; }
+                c => return Err(RleError(format!("caractère inattendu: {c:?}"))),
+            };
+            count.clear();
+            if let Some(v) = value {
+                // répétitions bornées par l'en-tête (ou `MAX_SIDE`) avant d'allouer quoi que ce soit
+                let end = x.checked_add(n).filter(|&e| e <= max_w).ok_or_else(|| RleError(format!("rangée de plus de {max_w} cellules")))?;
+                if y >= max_h {
+                    return Err(RleError(format!("plus de {max_h} rangées")));
+                }
+                match &mut grid {
+                    Some(g) => (x..end).for_each(|i| g.set(i, y, v)),
+                    None if v == 0 => {}
+                    None if cells.len() + n > MAX_LOOSE_CELLS => {
+                        return Err(RleError(format!("plus de {MAX_LOOSE_CELLS} cellules sans en-tête")));
+                    }
+                    None => cells.extend((x..end).map(|i| (i, y, v))),
+                }
+                x = end;
+                w = w.max(x);
+            }
+        }
+    }
+
+    if let Some(g) = grid {
+        return Ok(g);
+    }
+    let h = cells.iter().map(|c| c.1 + 1).max().unwrap_or(0);
+    if w == 0 || h == 0 {
+        return Err(RleError("grille vide".into()));
+    }
+    let mut g = Grid::new(w, h);
+    for (x, y, v) in cells {
+        if !g.try_set(x, y, v) {
+            return Err(RleError(format!("cellule ({x}, {y}) hors de {w}×{h}")));
+        }
+    }
+    Ok(g)
+}
+
+// --- bibliothèque de fragments : `stamps/<nom>.rle`
+
+pub fn path_in_dir(file_name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(STAMPS_DIR).join(file_name)
+}
+
+pub fn list_stamps() -> Vec<String> {
+    let mut out: Vec<String> = fs::read_dir(path_in_dir(""))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|s| s.to_str()) == Some(EXT))
+        .filter_map(|p| p.file_stem().and_then(|s| s.to_str()).map(str::to_string))
+        .collect();
+    out.sort();
+    out
+}
+
+// Nom de fragment vérifié par `io::check_name` : rien hors de `stamps/`.
+pub fn save_stamp(name: &str, grid: &Grid, rule: Option<&str>) -> std::io::Result<PathBuf> {
+    io::check_name(name)?;
+    fs::create_dir_all(path_in_dir(""))?;
+    let path = path_in_dir(&format!("{name}.{EXT}"));
+    fs::write(&path, to_rle(grid, rule))?;
+    Ok(path)
+}
+
+pub fn load_stamp(name: &str) -> Result<Grid, RleError> {
+    io::check_name(name).map_err(|e| RleError(e.to_string()))?;
+    let text = fs::read_to_string(path_in_dir(&format!("{name}.{EXT}"))).map_err(|e| RleError(e.to_string()))?;
+    from_rle(&text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut g = Grid::new(6, 4);
+        g.set(0, 0, 1);
+        g.set(5, 1, 2);
+        g.set(2, 3, 30);
+        let back = from_rle(&to_rle(&g, Some("test"))).expect("RLE valide");
+        assert_eq!((back.width(), back.height()), (6, 4));
+        assert_eq!(back.as_slice(), g.as_slice());
+    }
+
+    #[test]
+    fn huge_counts_are_rejected() {
+        assert!(from_rle("99999999999o!").is_err());
+        assert!(from_rle("99999999999999999999999o!").is_err());
+        assert!(from_rle("x = 3, y = 3\n4o!").is_err());
+        assert!(from_rle("x = 3, y = 3\n3o4$o!").is_err());
+        assert!(from_rle("x = 100000, y = 100000\no!").is_err());
+        assert!(from_rle("99999999999$o!").is_err());
+        assert!(from_rle("x = 3, y = 3\n3o2$3o!").is_ok());
+        // quelques octets sans en-tête ne doivent pas réserver des millions de cellules
+        assert!(from_rle("16777216o!").is_err());
+        assert!(from_rle("16000o$16000o$16000o$16000o$16000o!").is_err());
+        assert!(from_rle("x = 0, y = 3\n!").is_err());
+    }
+
+    #[test]
+    fn stamp_names_stay_in_the_directory() {
+        let g = Grid::new(2, 2);
+        for name in ["../../src/main", "a/b", "a\\b", "c:x", ".hidden", "..", "", "a\nb"] {
+            assert!(save_stamp(name, &g, None).is_err(), "{name:?}");
+            assert!(load_stamp(name).is_err(), "{name:?}");
+        }
+    }
+}
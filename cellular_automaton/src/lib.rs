@@ -1,7 +1,15 @@
+pub mod analysis;
 pub mod automaton;
 pub mod automata;
+pub mod cycle;
 pub mod engine;
 pub mod grid;
+pub mod palette;
 pub mod render;
+pub mod reversible;
+pub mod scheme;
+pub mod seeding;
+pub mod stats;
 pub mod ui;
-pub mod io;
\ No newline at end of file
+pub mod io;
+pub mod library;
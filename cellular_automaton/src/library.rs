@@ -0,0 +1,173 @@
+use crate::{grid::Grid, io::rle};
+
+// Catalogue de motifs fournis (RLE), avec l'automate qui les fait vivre.
+// Wireworld en RLE multi-états : A tête, B queue, C conducteur.
+// Labyrinthes : A mur, B départ, C arrivée.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Category {
+    StillLife,
+    Oscillator,
+    Spaceship,
+    Gun,
+    Methuselah,
+    Wireworld,
+    Maze,
+}
+
+impl Category {
+    pub const ALL: [Category; 7] = [
+        Category::StillLife, Category::Oscillator, Category::Spaceship, Category::Gun,
+        Category::Methuselah, Category::Wireworld, Category::Maze,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Category::StillLife => "still lifes",
+            Category::Oscillator => "oscillators",
+            Category::Spaceship => "spaceships",
+            Category::Gun => "guns",
+            Category::Methuselah => "methuselahs",
+            Category::Wireworld => "wireworld",
+            Category::Maze => "maze tests",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Entry {
+    pub name: &'static str,
+    pub category: Category,
+    // nom pour `automata::by_name`
+    pub automaton: &'static str,
+    pub description: &'static str,
+    // période (oscillateurs, vaisseaux, canons)
+    pub period: Option<u32>,
+    rle: &'static str,
+}
+
+impl Entry {
+    pub fn grid(&self) -> Grid {
+        rle::from_rle(self.rle).expect("motif du catalogue valide")
+    }
+
+    // Marge autour du motif sur une grille neuve : large pour ce qui se déplace ou grandit.
+    // Les tirs d'un canon font le tour du tore et reviennent sur lui, d'autant plus tard
+    // que la marge est grande.
+    pub fn margin(&self) -> usize {
+        match self.category {
+            Category::Gun => 100,
+            Category::Spaceship => 30,
+            Category::Methuselah => 40,
+            Category::Maze => 0,
+            _ => 4,
+        }
+    }
+
+    // Grille neuve à la bonne taille, motif au centre.
+    pub fn fresh_grid(&self) -> Grid {
+        let p = self.grid();
+        let m = self.margin();
+        let (w, h) = (p.width() + 2 * m, p.height() + 2 * m);
+        let mut g = Grid::new(w, h);
+        g.stamp(&p, m, m, true);
+        g
+    }
+}
+
+const fn entry(name: &'static str, category: Category, automaton: &'static str, period: Option<u32>, description: &'static str, rle: &'static str) -> Entry {
+    Entry { name, category, automaton, description, period, rle }
+}
+
+pub const CATALOGUE: &[Entry] = &[
+    // --- vie
+    entry("block", Category::StillLife, "life", None, "2×2, le plus petit point fixe", "2o$2o!"),
+    entry("beehive", Category::StillLife, "life", None, "ruche, 6 cellules", "b2o$o2bo$b2o!"),
+    entry("loaf", Category::StillLife, "life", None, "pain, 7 cellules", "b2o$o2bo$bobo$2bo!"),
+    entry("boat", Category::StillLife, "life", None, "bateau, 5 cellules", "2o$obo$bo!"),
+
+    entry("blinker", Category::Oscillator, "life", Some(2), "clignotant, 3 cellules", "3o!"),
+    entry("toad", Category::Oscillator, "life", Some(2), "crapaud", "b3o$3o!"),
+    entry("beacon", Category::Oscillator, "life", Some(2), "phare : deux blocs qui clignotent", "2o$2o$2b2o$2b2o!"),
+    entry("pulsar", Category::Oscillator, "life", Some(3), "pulsar, symétrie D4",
+        "2b3o3b3o2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2$2b3o3b3o$o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!"),
+    entry("pentadecathlon", Category::Oscillator, "life", Some(15), "période 15", "2bo4bo$2ob4ob2o$2bo4bo!"),
+
+    entry("glider", Category::Spaceship, "life", Some(4), "planeur, c/4 en diagonale", "bo$2bo$3o!"),
+    entry("lwss", Category::Spaceship, "life", Some(4), "petit vaisseau, c/2 orthogonal", "bo2bo$o$o3bo$4o!"),
+    entry("mwss", Category::Spaceship, "life", Some(4), "vaisseau moyen, c/2 orthogonal", "3bo$bo3bo$o$o4bo$5o!"),
+    entry("hwss", Category::Spaceship, "life", Some(4), "gros vaisseau, c/2 orthogonal", "3b2o$bo4bo$o$o5bo$6o!"),
+
+    entry("gosper gun", Category::Gun, "life", Some(30),
+        "canon de Gosper : un planeur tous les 30 pas ; sur le tore, ses planeurs reviennent le détruire (vers la génération 860 sur une grille neuve)",
+        "24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!"),
+
+    entry("r-pentomino", Category::Methuselah, "life", None, "se stabilise après 1103 générations", "b2o$2o$bo!"),
+    entry("acorn", Category::Methuselah, "life", None, "gland : 5206 générations avant stabilisation", "bo$3bo$2o2b3o!"),
+
+    // --- wireworld
+    entry("clock", Category::Wireworld, "wireworld", Some(10), "boucle avec un électron qui alimente un fil",
+        "CBA3C$C4.C10C$6C!"),
+    entry("diode", Category::Wireworld, "wireworld", None, "laisse passer l'électron de gauche à droite",
+        "3.2C$BA2C.5C$3.2C!"),
+    entry("diode (blocked)", Category::Wireworld, "wireworld", None, "la même diode bloque l'électron venu de droite",
+        "3.2C$4C.3CAB$3.2C!"),
+
+    // --- cas de test du solveur
+    entry("corridor", Category::Maze, "mazesolver", None, "couloir en serpentin, chemin unique",
+        "11A$AB8.A$9A.A$A9.A$A.9A$A8.CA$11A!"),
+    entry("rooms", Category::Maze, "mazesolver", None, "deux salles ouvertes : le trajet doit rester le plus court",
+        "15A$AB4.A7.A$A5.A7.A$A5.A3.A3.A$A9.A3.A$A5.A3.A2.CA$15A!"),
+    entry("no path", Category::Maze, "mazesolver", None, "arrivée inaccessible : aucun trajet",
+        "9A$AB2.A3.A$A3.A3.A$A3.A.C.A$9A!"),
+];
+
+pub fn by_name(name: &str) -> Option<&'static Entry> {
+    CATALOGUE.iter().find(|e| e.name.eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{automata, engine::Engine, scheme::UpdateScheme};
+
+    fn engine(e: &Entry) -> Engine {
+        let automaton = automata::by_name(e.automaton).expect("automate du catalogue");
+        let g = e.fresh_grid();
+        let mut engine = Engine::new(g.width(), g.height(), UpdateScheme::Synchronous, automaton.clone());
+        engine.restore(g, automaton).expect("grille assez grande");
+        engine.soft_init();
+        engine
+    }
+
+    #[test]
+    fn declared_periods() {
+        for e in CATALOGUE.iter().filter(|e| e.category != Category::Gun) {
+            let Some(period) = e.period else { continue };
+            let mut engine = engine(e);
+            while engine.cycle().is_none() && engine.generation() < 200 {
+                engine.step_once();
+            }
+            assert_eq!(engine.cycle().map(|c| c.period), Some(period as u64), "{}", e.name);
+        }
+    }
+
+    #[test]
+    fn gun_survives_its_first_gliders() {
+        let e = by_name("gosper gun").expect("canon du catalogue");
+        let mut engine = engine(e);
+        let m = e.margin();
+        let (w, h) = (e.grid().width(), e.grid().height());
+        let gun = |engine: &Engine| engine.current().region(m, m, w, h);
+        for _ in 0..60 {
+            engine.step_once();
+        }
+        let start = gun(&engine);
+        for _ in 0..16 {
+            for _ in 0..30 {
+                engine.step_once();
+            }
+            assert_eq!(gun(&engine).as_slice(), start.as_slice(), "génération {}", engine.generation());
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use std::{fmt, fs, path::{Path, PathBuf}};
+
+use crate::{automaton::StateInfo, io};
+
+// Palette : couleurs qui remplacent celles du schéma de l'automate, indexées par valeur.
+// Les états au-delà de la palette gardent la couleur du schéma ; palette vide = couleurs
+// de l'automate.
+//
+// Fichiers `palettes/<nom>.toml` (sous-ensemble de TOML) :
+//   name = "print"
+//   colors = ["#ffffff", "#000000", "#0072b2"]
+// ou un dégradé entre plusieurs couleurs, sur `steps` états :
+//   gradient = ["#000000", "#ff0000", "#ffff00"]
+//   steps = 16
+
+pub const PALETTES_DIR: &str = "palettes";
+pub const EXT: &str = "toml";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Palette {
+    pub name: String,
+    pub colors: Vec<[u8; 3]>,
+}
+
+#[derive(Debug)]
+pub struct PaletteError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for PaletteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // ligne 0 : erreur sur le fichier entier
+        match self.line {
+            0 => write!(f, "{}", self.message),
+            l => write!(f, "ligne {l}: {}", self.message),
+        }
+    }
+}
+
+fn err(line: usize, message: impl Into<String>) -> PaletteError {
+    PaletteError { line, message: message.into() }
+}
+
+fn parse_color(s: &str) -> Option<[u8; 3]> {
+    let hex = s.trim().trim_matches('"').strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let c = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some([c(0)?, c(2)?, c(4)?])
+}
+
+fn to_hex([r, g, b]: [u8; 3]) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+// Chaîne TOML entre guillemets : seuls `\"`, `\\` et `\n` sont échappés.
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+}
+
+fn unquote(s: &str) -> Option<String> {
+    let inner = s.strip_prefix('"')?.strip_suffix('"')?;
+    let mut out = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                c @ ('"' | '\\') => out.push(c),
+                'n' => out.push('\n'),
+                _ => return None,
+            },
+            '"' => return None,
+            c => out.push(c),
+        }
+    }
+    Some(out)
+}
+
+// Interpolation linéaire par morceaux entre les `stops`, sur n couleurs.
+pub fn gradient(stops: &[[u8; 3]], n: usize) -> Vec<[u8; 3]> {
+    match stops {
+        [] => Vec::new(),
+        [c] => vec![*c; n],
+        _ => (0..n)
+            .map(|i| {
+                let t = if n > 1 { i as f32 / (n - 1) as f32 } else { 0.0 } * (stops.len() - 1) as f32;
+                let k = (t as usize).min(stops.len() - 2);
+                let f = t - k as f32;
+                let (a, b) = (stops[k], stops[k + 1]);
+                [0, 1, 2].map(|j| (a[j] as f32 + (b[j] as f32 - a[j] as f32) * f).round() as u8)
+            })
+            .collect(),
+    }
+}
+
+impl Palette {
+    pub fn new(name: &str, colors: Vec<[u8; 3]>) -> Self {
+        Self { name: name.to_string(), colors }
+    }
+
+    // Palettes fournies : couleurs de l'automate, daltonisme (Okabe-Ito), impression
+    // (fond clair) et dégradé pour les règles à nombreux états.
+    pub fn builtin() -> Vec<Self> {
+        vec![
+            Self::new("automaton", Vec::new()),
+            Self::new("colorblind", vec![
+                [0, 0, 0], [240, 240, 240], [0, 114, 178], [213, 94, 0],
+                [0, 158, 115], [86, 180, 233], [204, 121, 167], [240, 228, 66],
+            ]),
+            Self::new("print", vec![
+                [255, 255, 255], [0, 0, 0], [0, 114, 178], [213, 94, 0],
+                [0, 158, 115], [170, 170, 170], [204, 121, 167], [230, 159, 0],
+            ]),
+            Self::new("heat", gradient(&[[0, 0, 0], [176, 0, 0], [255, 208, 0], [255, 255, 255]], 16)),
+        ]
+    }
+
+    // Palette fournie, sinon fichier `palettes/<nom>.toml`, sinon chemin direct.
+    pub fn by_name(name: &str) -> Result<Self, PaletteError> {
+        if let Some(p) = Self::builtin().into_iter().find(|p| p.name.eq_ignore_ascii_case(name)) {
+            return Ok(p);
+        }
+        let file = path_in_dir(&format!("{name}.{EXT}"));
+        Self::load(if file.is_file() { file } else { PathBuf::from(name) })
+    }
+
+    // Applique la palette au schéma d'un automate.
+    pub fn apply(&self, states: &mut [StateInfo]) {
+        for (s, &c) in states.iter_mut().zip(&self.colors) {
+            s.color = c;
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self, PaletteError> {
+        let mut name = String::new();
+        let mut colors = None;
+        let mut stops = None;
+        let mut steps = None;
+
+        for (i, raw) in text.lines().enumerate() {
+            let line = i + 1;
+            // '#' ouvre aussi les couleurs : commentaires seulement en début de ligne
+            let l = raw.trim();
+            if l.is_empty() || l.starts_with('#') {
+                continue;
+            }
+
+            let Some((key, value)) = l.split_once('=') else {
+                return Err(err(line, "attendu: clé = valeur"));
+            };
+            let value = value.trim();
+            let list = || -> Result<Vec<[u8; 3]>, PaletteError> {
+                let inner = value.strip_prefix('[').and_then(|v| v.strip_suffix(']'))
+                    .ok_or_else(|| err(line, "liste attendue: [\"#rrggbb\", ...]"))?;
+                inner.split(',').filter(|s| !s.trim().is_empty())
+                    .map(|s| parse_color(s).ok_or_else(|| err(line, format!("couleur invalide: {}", s.trim()))))
+                    .collect()
+            };
+
+            match key.trim() {
+                "name" => name = unquote(value).ok_or_else(|| err(line, "nom attendu: \"...\""))?,
+                "colors" => colors = Some(list()?),
+                "gradient" => stops = Some(list()?),
+                "steps" => steps = Some(value.parse::<usize>().map_err(|_| err(line, "nombre attendu"))?),
+                k => return Err(err(line, format!("clé inconnue: {k}"))),
+            }
+        }
+
+        let colors = match (colors, stops) {
+            (Some(c), None) => c,
+            (None, Some(s)) => gradient(&s, steps.unwrap_or(s.len()).min(256)),
+            (None, None) => Vec::new(),
+            (Some(_), Some(_)) => return Err(err(0, "`colors` et `gradient` sont exclusifs")),
+        };
+        Ok(Self { name, colors })
+    }
+
+    pub fn to_toml(&self) -> String {
+        let colors: Vec<String> = self.colors.iter().map(|&c| format!("\"{}\"", to_hex(c))).collect();
+        format!("name = {}\ncolors = [{}]\n", quote(&self.name), colors.join(", "))
+    }
+
+    // Sans `name`, la palette prend le nom du fichier.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, PaletteError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|e| err(0, e.to_string()))?;
+        let mut p = Self::parse(&text)?;
+        if p.name.is_empty() {
+            p.name = path.file_stem().and_then(|s| s.to_str()).unwrap_or("palette").to_string();
+        }
+        Ok(p)
+    }
+
+    // Écrit `palettes/<nom>.toml` (voir `io::check_name`).
+    pub fn save(&self) -> std::io::Result<PathBuf> {
+        io::check_name(&self.name)?;
+        fs::create_dir_all(path_in_dir(""))?;
+        let path = path_in_dir(&format!("{}.{EXT}", self.name));
+        fs::write(&path, self.to_toml())?;
+        Ok(path)
+    }
+}
+
+pub fn path_in_dir(file_name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(PALETTES_DIR).join(file_name)
+}
+
+// Noms des palettes : fournies puis fichiers.
+pub fn list() -> Vec<String> {
+    let mut out: Vec<String> = Palette::builtin().into_iter().map(|p| p.name).collect();
+    let mut files: Vec<String> = fs::read_dir(path_in_dir(""))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|s| s.to_str()) == Some(EXT))
+        .filter_map(|p| p.file_stem().and_then(|s| s.to_str()).map(str::to_string))
+        .filter(|n| !out.contains(n))
+        .collect();
+    files.sort();
+    out.extend(files);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for p in Palette::builtin().into_iter().chain([
+            Palette::new("quote \"ici\" et \\ là\n", vec![[1, 2, 3]]),
+            Palette::new("", Vec::new()),
+        ]) {
+            assert_eq!(Palette::parse(&p.to_toml()).expect("palette relue"), p);
+        }
+    }
+
+    #[test]
+    fn parse() {
+        let p = Palette::parse("# essai\nname = \"a\\\"b\"\ngradient = [\"#000000\", \"#ff0000\"]\nsteps = 3\n").expect("palette valide");
+        assert_eq!(p.name, "a\"b");
+        assert_eq!(p.colors, vec![[0, 0, 0], [128, 0, 0], [255, 0, 0]]);
+
+        assert!(Palette::parse("name = \"a\"b\"").is_err());
+        assert!(Palette::parse("name = a").is_err());
+        assert!(Palette::parse("colors = [\"#12345\"]").is_err());
+        assert!(Palette::parse("colors = []\ngradient = []").is_err());
+    }
+
+    #[test]
+    fn names_outside_the_directory_are_refused() {
+        for name in ["../x", "a/b", "..", ".cache", "a\\b", ""] {
+            assert!(Palette::new(name, Vec::new()).save().is_err(), "{name:?}");
+        }
+    }
+}
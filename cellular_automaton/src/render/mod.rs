@@ -1,8 +1,9 @@
 pub mod terminal;
 
-use crate::grid::Grid;
+use crate::{automaton::StateInfo, grid::Grid};
 
 pub trait Renderer {
     fn name(&self) -> &'static str;
-    fn render(&mut self, grid: &Grid);
+    // `states` : schéma de l'automate courant (couleurs, glyphes), indexé par valeur
+    fn render(&mut self, grid: &Grid, states: &[StateInfo]);
 }
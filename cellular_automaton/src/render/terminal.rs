@@ -1,9 +1,15 @@
 use std::io::{self, Write};
 
-use crate::{grid::Grid, render::Renderer};
+use crate::{automaton::StateInfo, grid::Grid, render::Renderer};
 
 pub struct TerminalRenderer;
 
+impl Default for TerminalRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TerminalRenderer {
     pub fn new() -> Self {
         Self
@@ -13,19 +19,40 @@ impl TerminalRenderer {
 impl Renderer for TerminalRenderer {
     fn name(&self) -> &'static str { "terminal" }
 
-    fn render(&mut self, grid: &Grid) {
+    fn render(&mut self, grid: &Grid, states: &[StateInfo]) {
         // efface écran + curseur en haut
         print!("\x1b[2J\x1b[H");
 
         for y in 0..grid.height() {
+            let mut line = String::new();
             for x in 0..grid.width() {
-                let v = grid.get(x, y);
-                let ch = if v == 0 { ' ' } else { '█' };
-                print!("{ch}");
+                // glyphe dans la couleur de l'état (true color), '?' hors schéma
+                let (ch, [r, g, b]) = match states.get(grid.get(x, y) as usize) {
+                    Some(s) => (s.glyph, s.color),
+                    None => ('?', [128, 128, 128]),
+                };
+                if ch == ' ' {
+                    line.push(' ');
+                } else {
+                    line.push_str(&format!("\x1b[38;2;{r};{g};{b}m{ch}"));
+                }
             }
-            println!();
+            println!("{line}\x1b[0m");
         }
 
+        // légende
+        let legend: Vec<String> = states.iter().enumerate()
+            .map(|(i, s)| {
+                let [r, g, b] = s.color;
+                if s.glyph == ' ' {
+                    format!("· {i}:{}", s.name)
+                } else {
+                    format!("\x1b[38;2;{r};{g};{b}m{}\x1b[0m {i}:{}", s.glyph, s.name)
+                }
+            })
+            .collect();
+        println!("{}", legend.join("  "));
+
         io::stdout().flush().ok();
     }
 }
@@ -0,0 +1,123 @@
+use crate::{automaton::Automaton, engine::Engine, grid::Grid, scheme::UpdateScheme};
+
+// Automates réversibles du second ordre (Fredkin) : toute règle locale f à k états donne
+// x(t+1) = f(x(t)) - x(t-1) mod k (un XOR pour k = 2). L'état est le couple (précédente,
+// courante) ; on recule par le même calcul, rôles échangés : x(t-1) = f(x(t)) - x(t+1).
+// f est appliquée de façon synchrone, quel que soit le schéma de l'engine.
+
+// Un pas : next = f(current) - previous (mod k).
+pub fn second_order(automaton: &dyn Automaton, previous: &Grid, current: &Grid, next: &mut Grid) {
+    let k = automaton.states().len().max(2) as u16;
+    for y in 0..current.height() {
+        for x in 0..current.width() {
+            let f = automaton.rule(current, x, y) as u16 % k;
+            let p = previous.get(x, y) as u16 % k;
+            next.set(x, y, ((f + k - p) % k) as u8);
+        }
+    }
+}
+
+// Vérifie l'inversion par l'engine lui-même (`step_once` / `step_back` en mode second ordre),
+// en mémoire constante : après chaque pas, un pas arrière redonne exactement l'état d'avant
+// (grille et précédente), puis `steps` pas arrière ramènent à l'état de départ.
+// Err(g) : première génération fautive (états hors de 0..k, par exemple), Err(0) si seul
+// l'aller-retour complet échoue.
+pub fn verify(automaton: &dyn Automaton, previous: &Grid, current: &Grid, steps: u64) -> Result<(), u64> {
+    let mut engine = Engine::new(current.width(), current.height(), UpdateScheme::Synchronous, automaton.box_clone());
+    let same = |e: &Engine, p: &Grid, c: &Grid| {
+        e.current().as_slice() == c.as_slice() && e.previous().is_some_and(|ep| ep.as_slice() == p.as_slice())
+    };
+    if engine.restore(current.clone(), automaton.box_clone()).is_err()
+        || !engine.set_second_order(true)
+        || !engine.set_previous(previous.clone())
+    {
+        return Err(0);
+    }
+
+    let (mut prev, mut cur) = (previous.clone(), current.clone());
+    for g in 1..=steps {
+        engine.step_once();
+        engine.step_back();
+        if !same(&engine, &prev, &cur) {
+            return Err(g);
+        }
+        engine.step_once();
+        prev.as_mut_slice().copy_from_slice(engine.previous().expect("second ordre").as_slice());
+        cur.as_mut_slice().copy_from_slice(engine.current().as_slice());
+    }
+
+    for _ in 0..steps {
+        engine.step_back();
+    }
+    if !same(&engine, previous, current) {
+        return Err(0);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use super::*;
+    use crate::automata;
+
+    // Grille aléatoire sur les k états de l'automate.
+    fn random_grid(k: u8) -> Grid {
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut g = Grid::new(48, 32);
+        for c in g.as_mut_slice() {
+            *c = rng.random_range(0..k);
+        }
+        g
+    }
+
+    // N pas en avant puis N en arrière (plus que l'historique de l'engine) : grille et
+    // précédente identiques octet pour octet.
+    fn round_trip(name: &str) {
+        let automaton = automata::by_name(name).expect("automate");
+        let k = automaton.states().len() as u8;
+        let mut engine = Engine::new(48, 32, UpdateScheme::Synchronous, automaton);
+        engine.set_grid(random_grid(k)).expect("taille suffisante");
+        assert!(engine.set_second_order(true));
+        for _ in 0..7 {
+            engine.step_once();
+        }
+        let current = engine.current().clone();
+        let previous = engine.previous().expect("second ordre").clone();
+        assert!(previous.as_slice().iter().any(|&v| v != 0));
+
+        for _ in 0..600 {
+            engine.step_once();
+        }
+        assert!(engine.current().as_slice() != current.as_slice(), "{name}: grille inchangée");
+        for _ in 0..600 {
+            assert!(engine.step_back());
+        }
+        assert!(engine.current().as_slice() == current.as_slice(), "{name}: grille différente");
+        assert!(engine.previous().is_some_and(|p| p.as_slice() == previous.as_slice()), "{name}: précédente différente");
+
+        assert_eq!(verify(engine.automaton(), &previous, &current, 200), Ok(()), "{name}");
+    }
+
+    #[test]
+    fn life_round_trip() {
+        round_trip("life");
+    }
+
+    #[test]
+    fn wireworld_round_trip() {
+        // 4 états : x(t+1) = f(x(t)) - x(t-1) mod 4
+        assert_eq!(automata::by_name("wireworld").map(|a| a.states().len()), Some(4));
+        round_trip("wireworld");
+    }
+
+    #[test]
+    fn states_outside_the_rule_are_caught() {
+        let automaton = automata::by_name("life").expect("automate");
+        let previous = Grid::new(8, 8);
+        let mut current = Grid::new(8, 8);
+        current.set(3, 3, 5);
+        assert!(verify(automaton.as_ref(), &previous, &current, 5).is_err());
+    }
+}
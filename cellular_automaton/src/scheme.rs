@@ -0,0 +1,253 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
+
+use crate::{automaton::Automaton, grid::Grid};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UpdateScheme {
+    // Toutes les cellules en même temps (double buffer).
+    Synchronous,
+    // α-asynchrone : chaque cellule est mise à jour avec probabilité α (double buffer).
+    Alpha(f32),
+    // Totalement asynchrone : w*h tirages uniformes, mise à jour en place.
+    FullyAsync,
+    // Balayage dans un ordre aléatoire fixé une fois pour toutes, en place.
+    RandomSweep,
+    // Horloges de Poisson (taux 1 par cellule), en place, dans l'ordre des tics.
+    Clock,
+    // Blocs 2x2 (voisinage de Margolus, décalage alterné), chaque bloc synchrone,
+    // blocs mis à jour en place dans un ordre aléatoire.
+    Block,
+}
+
+impl UpdateScheme {
+    pub const ALL: [UpdateScheme; 6] = [
+        UpdateScheme::Synchronous,
+        UpdateScheme::Alpha(0.5),
+        UpdateScheme::FullyAsync,
+        UpdateScheme::RandomSweep,
+        UpdateScheme::Clock,
+        UpdateScheme::Block,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            UpdateScheme::Synchronous => "synchronous",
+            UpdateScheme::Alpha(_) => "alpha",
+            UpdateScheme::FullyAsync => "async",
+            UpdateScheme::RandomSweep => "sweep",
+            UpdateScheme::Clock => "clock",
+            UpdateScheme::Block => "block",
+        }
+    }
+
+    // `alpha` n'est utilisé que pour le schéma α-asynchrone.
+    pub fn by_name(name: &str, alpha: f32) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|s| s.name().eq_ignore_ascii_case(name))
+            .map(|s| match s {
+                UpdateScheme::Alpha(_) => UpdateScheme::Alpha(alpha),
+                s => s,
+            })
+    }
+}
+
+// Applique la transition locale d'un automate selon un schéma de mise à jour.
+// Garde l'état propre aux schémas (permutation, horloges, phase des blocs).
+pub struct Scheduler {
+    scheme: UpdateScheme,
+    rng: StdRng,
+
+    // balayage aléatoire fixe
+    order: Vec<usize>,
+
+    // horloges : (date du prochain tic, indice de cellule)
+    clocks: BinaryHeap<Reverse<(u64, usize)>>,
+    time: f64,
+
+    // décalage des blocs de Margolus
+    phase: usize,
+}
+
+impl Scheduler {
+    pub fn new(scheme: UpdateScheme) -> Self {
+        Self {
+            scheme,
+            rng: StdRng::from_os_rng(),
+            order: Vec::new(),
+            clocks: BinaryHeap::new(),
+            time: 0.0,
+            phase: 0,
+        }
+    }
+
+    pub fn scheme(&self) -> UpdateScheme {
+        self.scheme
+    }
+
+    pub fn set_scheme(&mut self, scheme: UpdateScheme) {
+        if self.scheme.name() != scheme.name() {
+            self.reset();
+        }
+        self.scheme = scheme;
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.reset();
+    }
+
+    // Oublie l'état du schéma (à appeler quand la grille change de taille ou de contenu).
+    pub fn reset(&mut self) {
+        self.order.clear();
+        self.clocks.clear();
+        self.time = 0.0;
+        self.phase = 0;
+    }
+
+    // Un pas de temps. Le résultat est dans `current`, `next` sert de tampon.
+    pub fn apply(&mut self, automaton: &dyn Automaton, current: &mut Grid, next: &mut Grid) {
+        let w = current.width();
+        let h = current.height();
+
+        match self.scheme {
+            UpdateScheme::Synchronous => {
+                for y in 0..h {
+                    for x in 0..w {
+                        next.set(x, y, automaton.rule(current, x, y));
+                    }
+                }
+                current.swap(next);
+            }
+            UpdateScheme::Alpha(alpha) => {
+                for y in 0..h {
+                    for x in 0..w {
+                        let v = if self.rng.random::<f32>() < alpha {
+                            automaton.rule(current, x, y)
+                        } else {
+                            current.get(x, y)
+                        };
+                        next.set(x, y, v);
+                    }
+                }
+                current.swap(next);
+            }
+            UpdateScheme::FullyAsync => {
+                for _ in 0..w * h {
+                    let x = self.rng.random_range(..w);
+                    let y = self.rng.random_range(..h);
+                    let v = automaton.rule(current, x, y);
+                    current.set(x, y, v);
+                }
+            }
+            UpdateScheme::RandomSweep => {
+                if self.order.len() != w * h {
+                    self.order = (0..w * h).collect();
+                    self.order.shuffle(&mut self.rng);
+                }
+                for &i in &self.order {
+                    let (x, y) = (i % w, i / w);
+                    let v = automaton.rule(current, x, y);
+                    current.set(x, y, v);
+                }
+            }
+            UpdateScheme::Clock => {
+                if self.clocks.len() != w * h {
+                    self.clocks.clear();
+                    for i in 0..w * h {
+                        let t = self.time + self.exp_sample();
+                        self.clocks.push(Reverse((t.to_bits(), i)));
+                    }
+                }
+
+                self.time += 1.0;
+                while let Some(&Reverse((t, i))) = self.clocks.peek() {
+                    let t = f64::from_bits(t);
+                    if t >= self.time { break; }
+                    self.clocks.pop();
+
+                    let (x, y) = (i % w, i / w);
+                    let v = automaton.rule(current, x, y);
+                    current.set(x, y, v);
+
+                    let t = t + self.exp_sample();
+                    self.clocks.push(Reverse((t.to_bits(), i)));
+                }
+            }
+            UpdateScheme::Block => {
+                // blocs 2x2, décalés d'une case un pas sur deux. En tore sur une dimension
+                // paire ; sur une dimension impaire, seuls les blocs complets sans retour au
+                // bord (comme `BlockRule`) : chaque cellule est mise à jour au plus une fois.
+                let off = self.phase;
+                self.phase ^= 1;
+
+                let starts = |n: usize| (off..n + off).step_by(2).filter(move |&b| n.is_multiple_of(2) || b + 1 < n);
+                let mut blocks: Vec<(usize, usize)> = Vec::new();
+                for by in starts(h) {
+                    for bx in starts(w) {
+                        blocks.push((bx, by));
+                    }
+                }
+                blocks.shuffle(&mut self.rng);
+
+                for (bx, by) in blocks {
+                    let cells = [
+                        (bx % w, by % h),
+                        ((bx + 1) % w, by % h),
+                        (bx % w, (by + 1) % h),
+                        ((bx + 1) % w, (by + 1) % h),
+                    ];
+                    let out = cells.map(|(x, y)| automaton.rule(current, x, y));
+                    for ((x, y), v) in cells.into_iter().zip(out) {
+                        current.set(x, y, v);
+                    }
+                }
+            }
+        }
+    }
+
+    // Durée d'attente exponentielle de taux 1.
+    fn exp_sample(&mut self) -> f64 {
+        -(1.0 - self.rng.random::<f64>()).ln()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    // Compte les appels à `rule` par cellule.
+    #[derive(Clone)]
+    struct Counter(RefCell<Vec<usize>>);
+
+    impl Automaton for Counter {
+        fn name(&self) -> &str { "counter" }
+        fn init(&mut self, _grid: &mut Grid) {}
+        fn soft_init(&mut self, _grid: &mut Grid) {}
+        fn rule(&self, current: &Grid, x: usize, y: usize) -> u8 {
+            self.0.borrow_mut()[y * current.width() + x] += 1;
+            0
+        }
+    }
+
+    #[test]
+    fn block_updates_each_cell_at_most_once() {
+        for (w, h) in [(4, 4), (6, 2), (5, 4), (4, 5), (5, 5), (3, 3)] {
+            let mut scheduler = Scheduler::new(UpdateScheme::Block);
+            let (mut current, mut next) = (Grid::new(w, h), Grid::new(w, h));
+            for phase in 0..2 {
+                let counter = Counter(RefCell::new(vec![0; w * h]));
+                scheduler.apply(&counter, &mut current, &mut next);
+                let counts = counter.0.into_inner();
+                assert!(counts.iter().all(|&c| c <= 1), "{w}×{h} phase {phase}: {counts:?}");
+                if w % 2 == 0 && h % 2 == 0 {
+                    assert!(counts.iter().all(|&c| c == 1), "{w}×{h} phase {phase}: {counts:?}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use std::{fmt, path::Path};
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::{automaton::Automaton, grid::{D4, Grid, Transform}, io::{bin, rle}};
+
+// Conditions initiales réutilisables, appliquées par l'engine à la place de
+// `Automaton::init` (suivi de `soft_init`) ou par-dessus son résultat.
+// Un même `seed` redonne la même grille.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Symmetry {
+    // rotation de 180°
+    C2,
+    // rotations de 90°
+    C4,
+    // rotations et miroirs
+    D8,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 3] = [Symmetry::C2, Symmetry::C4, Symmetry::D8];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Symmetry::C2 => "c2",
+            Symmetry::C4 => "c4",
+            Symmetry::D8 => "d8",
+        }
+    }
+
+    fn group(self) -> &'static [Transform] {
+        match self {
+            Symmetry::C2 => &[D4[0], D4[2]],
+            Symmetry::C4 => &D4[..4],
+            Symmetry::D8 => &D4,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub enum Seed {
+    // chaque cellule reçoit l'état avec cette probabilité
+    Bernoulli(f32),
+    // soupe k×k au centre, de densité donnée
+    Soup(usize, f32),
+    // soupe k×k au centre, invariante par la symétrie
+    Symmetric(Symmetry, usize, f32),
+    // taches de bruit lissé (échelle en cellules), la part `density` la plus haute est posée
+    Blobs(f32, f32),
+    // une seule cellule au centre
+    Single,
+    // motif chargé d'un fichier (.rle ou .cagr), centré
+    Pattern(Grid),
+}
+
+impl Seed {
+    pub const NAMES: [&'static str; 8] = ["bernoulli", "soup", "c2", "c4", "d8", "blobs", "single", "file"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Seed::Bernoulli(_) => "bernoulli",
+            Seed::Soup(..) => "soup",
+            Seed::Symmetric(s, ..) => s.name(),
+            Seed::Blobs(..) => "blobs",
+            Seed::Single => "single",
+            Seed::Pattern(_) => "file",
+        }
+    }
+}
+
+// Où va la graine : à la place de `init`, ou posée sur la grille d'`init` (ses 0 ne recouvrent rien).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Placement {
+    Instead,
+    Over,
+}
+
+#[derive(Debug)]
+pub struct SeedError(pub String);
+
+impl fmt::Display for SeedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "graine: {}", self.0)
+    }
+}
+
+#[derive(Clone)]
+pub struct Seeding {
+    pub seed: Seed,
+    pub value: u64,
+    // état posé par les graines aléatoires
+    pub state: u8,
+    pub placement: Placement,
+}
+
+impl Seeding {
+    pub fn new(seed: Seed, value: u64) -> Self {
+        Self { seed, value, state: 1, placement: Placement::Instead }
+    }
+
+    // `.rle` au format de Golly, sinon grille binaire `.cagr`.
+    pub fn from_file(path: impl AsRef<Path>, value: u64) -> Result<Self, SeedError> {
+        let path = path.as_ref();
+        let grid = if path.extension().is_some_and(|e| e == rle::EXT) {
+            let text = std::fs::read_to_string(path).map_err(|e| SeedError(format!("{}: {e}", path.display())))?;
+            rle::from_rle(&text).map_err(|e| SeedError(e.to_string()))?
+        } else {
+            bin::load(path).map_err(|e| SeedError(format!("{}: {e:?}", path.display())))?
+        };
+        Ok(Self::new(Seed::Pattern(grid), value))
+    }
+
+    // `bernoulli[:d]`, `soup[:k[:d]]`, `c2|c4|d8[:k[:d]]`, `blobs[:échelle[:d]]`, `single`, `file:<chemin>`.
+    pub fn parse(spec: &str, value: u64) -> Result<Self, SeedError> {
+        let (kind, rest) = spec.split_once(':').unwrap_or((spec, ""));
+        if kind == "file" {
+            return Self::from_file(rest, value);
+        }
+        let params: Vec<&str> = rest.split(':').filter(|s| !s.is_empty()).collect();
+        let num = |i: usize, default: f32| -> Result<f32, SeedError> {
+            params.get(i).map_or(Ok(default), |s| s.parse().map_err(|_| SeedError(format!("paramètre invalide: {s}"))))
+        };
+        let seed = match kind {
+            "bernoulli" => Seed::Bernoulli(num(0, 0.5)?),
+            "soup" => Seed::Soup(num(0, 16.0)? as usize, num(1, 0.5)?),
+            "blobs" => Seed::Blobs(num(0, 8.0)?, num(1, 0.4)?),
+            "single" => Seed::Single,
+            s => match Symmetry::ALL.into_iter().find(|y| y.name() == s) {
+                Some(sym) => Seed::Symmetric(sym, num(0, 16.0)? as usize, num(1, 0.5)?),
+                None => return Err(SeedError(format!("inconnue: {s}"))),
+            },
+        };
+        let max = match seed {
+            Seed::Single => 0,
+            Seed::Bernoulli(_) => 1,
+            _ => 2,
+        };
+        if params.len() > max {
+            return Err(SeedError(format!("{kind}: au plus {max} paramètre(s)")));
+        }
+        Ok(Self::new(seed, value))
+    }
+
+    // Grille w×h remplie par la graine seule.
+    pub fn generate(&self, w: usize, h: usize) -> Grid {
+        let mut rng = StdRng::seed_from_u64(self.value);
+        let mut g = Grid::new(w, h);
+        let v = self.state;
+        match &self.seed {
+            &Seed::Bernoulli(d) => {
+                for c in g.as_mut_slice() {
+                    if rng.random::<f32>() < d {
+                        *c = v;
+                    }
+                }
+            }
+            &Seed::Soup(k, d) => {
+                let (kx, ky) = (k.min(w), k.min(h));
+                for y in (h - ky) / 2..(h - ky) / 2 + ky {
+                    for x in (w - kx) / 2..(w - kx) / 2 + kx {
+                        if rng.random::<f32>() < d {
+                            g.set(x, y, v);
+                        }
+                    }
+                }
+            }
+            &Seed::Symmetric(sym, k, d) => {
+                let k = k.min(w).min(h);
+                let soup = symmetric_soup(&mut rng, sym, k, d, v);
+                g.stamp(&soup, (w - k) / 2, (h - k) / 2, false);
+            }
+            &Seed::Blobs(scale, d) => {
+                let noise: Vec<f32> = (0..w * h)
+                    .map(|i| fractal_noise(self.value, (i % w) as f32 / scale.max(1.0), (i / w) as f32 / scale.max(1.0)))
+                    .collect();
+                // seuil au quantile 1 - d : la densité est celle demandée
+                let mut sorted = noise.clone();
+                sorted.sort_by(f32::total_cmp);
+                let n = ((1.0 - d.clamp(0.0, 1.0)) * sorted.len() as f32) as usize;
+                let threshold = sorted.get(n).copied().unwrap_or(f32::INFINITY);
+                for (c, &z) in g.as_mut_slice().iter_mut().zip(&noise) {
+                    if z >= threshold {
+                        *c = v;
+                    }
+                }
+            }
+            Seed::Single => g.set(w / 2, h / 2, v),
+            Seed::Pattern(p) => {
+                let x = (w as isize - p.width() as isize).div_euclid(2).rem_euclid(w as isize);
+                let y = (h as isize - p.height() as isize).div_euclid(2).rem_euclid(h as isize);
+                g.stamp(p, x as usize, y as usize, false);
+            }
+        }
+        g
+    }
+
+    // Remplace `init` : graine puis `soft_init` ; ou `init` puis graine par-dessus.
+    pub fn apply(&self, automaton: &mut dyn Automaton, grid: &mut Grid) {
+        let seeded = self.generate(grid.width(), grid.height());
+        match self.placement {
+            Placement::Instead => {
+                *grid = seeded;
+                automaton.soft_init(grid);
+            }
+            Placement::Over => {
+                automaton.init(grid);
+                grid.stamp(&seeded, 0, 0, true);
+            }
+        }
+    }
+}
+
+// Soupe k×k : chaque orbite de la symétrie (autour du centre) reçoit un seul tirage.
+fn symmetric_soup(rng: &mut StdRng, sym: Symmetry, k: usize, density: f32, value: u8) -> Grid {
+    let mut g = Grid::new(k, k);
+    let mut done = vec![false; k * k];
+    // coordonnées doublées, centrées : (2x - k + 1) reste entier pour k pair
+    let c = k as i32 - 1;
+    for i in 0..k * k {
+        if done[i] {
+            continue;
+        }
+        let on = rng.random::<f32>() < density;
+        let (x, y) = ((i % k) as i32 * 2 - c, (i / k) as i32 * 2 - c);
+        for t in sym.group() {
+            let (tx, ty) = t(x, y);
+            let j = ((ty + c) / 2) as usize * k + ((tx + c) / 2) as usize;
+            done[j] = true;
+            if on {
+                g.as_mut_slice()[j] = value;
+            }
+        }
+    }
+    g
+}
+
+// Valeur pseudo-aléatoire dans [0, 1) d'un nœud du réseau (splitmix64).
+fn lattice(seed: u64, x: i64, y: i64) -> f32 {
+    let mut z = seed ^ (x as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ (y as u64).wrapping_mul(0xc2b2_ae3d_27d4_eb4f);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^= z >> 31;
+    (z >> 40) as f32 / (1u64 << 24) as f32
+}
+
+// Bruit de valeur lissé (interpolation smoothstep entre nœuds entiers).
+fn value_noise(seed: u64, x: f32, y: f32) -> f32 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let (sx, sy) = (fx * fx * (3.0 - 2.0 * fx), fy * fy * (3.0 - 2.0 * fy));
+    let (ix, iy) = (x0 as i64, y0 as i64);
+    let top = lattice(seed, ix, iy) * (1.0 - sx) + lattice(seed, ix + 1, iy) * sx;
+    let bottom = lattice(seed, ix, iy + 1) * (1.0 - sx) + lattice(seed, ix + 1, iy + 1) * sx;
+    top * (1.0 - sy) + bottom * sy
+}
+
+// Trois octaves, chacune deux fois plus fine et moitié moins forte.
+fn fractal_noise(seed: u64, x: f32, y: f32) -> f32 {
+    (0..3).map(|o| {
+        let f = (1 << o) as f32;
+        value_noise(seed.wrapping_add(o), x * f, y * f) / f
+    }).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPECS: [&str; 7] = ["bernoulli", "soup:8", "c2:10", "c4:11", "d8:12", "blobs:4:0.3", "single"];
+
+    #[test]
+    fn same_seed_same_grid() {
+        for spec in SPECS {
+            let a = Seeding::parse(spec, 7).expect("graine valide");
+            let g = a.generate(32, 24);
+            assert!(g.as_slice().contains(&1), "{spec}");
+            assert_eq!(g.as_slice(), Seeding::parse(spec, 7).expect("graine valide").generate(32, 24).as_slice(), "{spec}");
+            if spec != "single" {
+                assert_ne!(g.as_slice(), Seeding::parse(spec, 8).expect("graine valide").generate(32, 24).as_slice(), "{spec}");
+            }
+        }
+    }
+
+    #[test]
+    fn symmetric_seeds_are_invariant() {
+        for sym in Symmetry::ALL {
+            for k in [11, 12] {
+                // grille k×k : la soupe la couvre entièrement
+                let g = Seeding::new(Seed::Symmetric(sym, k, 0.5), 3).generate(k, k);
+                assert!(g.as_slice().iter().any(|&v| v != 0));
+                for &t in sym.group() {
+                    assert_eq!(g.transformed(t).as_slice(), g.as_slice(), "{} k={k}", sym.name());
+                }
+            }
+        }
+        // sans la symétrie demandée, une soupe quelconque n'est pas invariante
+        let soup = Seeding::new(Seed::Soup(12, 0.5), 3).generate(12, 12);
+        assert!(D4[1..].iter().any(|&t| soup.transformed(t).as_slice() != soup.as_slice()));
+    }
+
+    #[test]
+    fn blobs_density_and_placement() {
+        let g = Seeding::new(Seed::Blobs(6.0, 0.25), 1).generate(40, 40);
+        assert_eq!(g.as_slice().iter().filter(|&&v| v != 0).count(), 400);
+
+        let mut p = Grid::new(2, 1);
+        p.set(0, 0, 3);
+        let g = Seeding::new(Seed::Pattern(p), 0).generate(6, 5);
+        assert_eq!(g.get(2, 2), 3);
+        assert_eq!(g.as_slice().iter().filter(|&&v| v != 0).count(), 1);
+    }
+
+    #[test]
+    fn parse_errors() {
+        for bad in ["nope", "soup:x", "c6:10", "bernoulli:0.5:0.2", "single:3", "d8:8:0.5:1"] {
+            assert!(Seeding::parse(bad, 0).is_err(), "{bad}");
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use std::{collections::VecDeque, fmt::Write as _};
+
+use crate::{automaton::Automaton, grid::Grid};
+
+// Nombre de mesures conservées (les plus anciennes sont oubliées).
+const MAX_SAMPLES: usize = 4096;
+
+// Mesure d'une génération. Naissance : 0 -> autre état, mort : autre état -> 0.
+#[derive(Clone, Debug, Default)]
+pub struct Sample {
+    pub generation: u64,
+    // nombre de cellules par état
+    pub counts: Vec<u64>,
+    pub births: u64,
+    pub deaths: u64,
+    // part des cellules non nulles
+    pub density: f32,
+    // (x0, y0, x1, y1) inclusif des cellules non nulles
+    pub bbox: Option<(usize, usize, usize, usize)>,
+    // mesures propres à l'automate (`Automaton::metrics`)
+    pub metrics: Vec<(&'static str, f64)>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    samples: VecDeque<Sample>,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Self { samples: VecDeque::new() }
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    pub fn samples(&self) -> &VecDeque<Sample> {
+        &self.samples
+    }
+
+    pub fn last(&self) -> Option<&Sample> {
+        self.samples.back()
+    }
+
+    // Oublie la dernière mesure (retour en arrière).
+    pub fn pop(&mut self) {
+        self.samples.pop_back();
+    }
+
+    // Mesure `grid` ; `prev` (génération précédente) donne naissances et morts.
+    pub fn record(&mut self, generation: u64, prev: Option<&Grid>, grid: &Grid, automaton: &dyn Automaton) {
+        let w = grid.width();
+        let mut counts = Vec::new();
+        let mut bbox: Option<(usize, usize, usize, usize)> = None;
+
+        for (i, &v) in grid.as_slice().iter().enumerate() {
+            if counts.len() <= v as usize {
+                counts.resize(v as usize + 1, 0);
+            }
+            counts[v as usize] += 1;
+
+            if v != 0 {
+                let (x, y) = (i % w, i / w);
+                bbox = Some(match bbox {
+                    None => (x, y, x, y),
+                    Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+                });
+            }
+        }
+
+        let (mut births, mut deaths) = (0, 0);
+        if let Some(p) = prev.filter(|p| p.cell_count() == grid.cell_count()) {
+            for (&a, &b) in p.as_slice().iter().zip(grid.as_slice()) {
+                births += (a == 0 && b != 0) as u64;
+                deaths += (a != 0 && b == 0) as u64;
+            }
+        }
+
+        let alive = grid.cell_count() as u64 - counts.first().copied().unwrap_or(0);
+        let density = alive as f32 / grid.cell_count().max(1) as f32;
+
+        if self.samples.len() == MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(Sample { generation, counts, births, deaths, density, bbox, metrics: automaton.metrics(grid) });
+    }
+
+    // Une ligne par mesure ; colonnes d'états et de mesures réunies sur tout l'historique.
+    pub fn to_csv(&self) -> String {
+        let states = self.samples.iter().map(|s| s.counts.len()).max().unwrap_or(0);
+        let mut metrics: Vec<&str> = Vec::new();
+        for s in &self.samples {
+            for (name, _) in &s.metrics {
+                if !metrics.contains(name) {
+                    metrics.push(name);
+                }
+            }
+        }
+
+        let mut out = String::from("generation");
+        for i in 0..states {
+            let _ = write!(out, ",state_{i}");
+        }
+        out.push_str(",births,deaths,density,bbox_x0,bbox_y0,bbox_x1,bbox_y1");
+        for m in &metrics {
+            let _ = write!(out, ",{m}");
+        }
+        out.push('\n');
+
+        for s in &self.samples {
+            let _ = write!(out, "{}", s.generation);
+            for i in 0..states {
+                let _ = write!(out, ",{}", s.counts.get(i).copied().unwrap_or(0));
+            }
+            let _ = write!(out, ",{},{},{:.6}", s.births, s.deaths, s.density);
+            match s.bbox {
+                Some((x0, y0, x1, y1)) => { let _ = write!(out, ",{x0},{y0},{x1},{y1}"); }
+                None => out.push_str(",,,,"),
+            }
+            for m in &metrics {
+                match s.metrics.iter().find(|(n, _)| n == m) {
+                    Some((_, v)) => { let _ = write!(out, ",{v}"); }
+                    None => out.push(','),
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
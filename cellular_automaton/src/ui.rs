@@ -1,25 +1,180 @@
-use std::time::{Duration, Instant};
+use std::{sync::{Arc, atomic::{AtomicUsize, Ordering}}, thread, time::{Duration, Instant}};
 use eframe::egui;
-use crate::{automata, engine::Engine};
+use crate::{analysis::{explore::{self, ExploreConfig, RuleRange, RuleReport}, spatial::{self, SpatialReport}, sweep::{self, SweepConfig, SweepPoint}}, automata, automaton::StateInfo, engine::{Engine, Resize, SizeError, Until}, grid::{Grid, FLIP, ROTATE}, io::rle, library::{self, Category}, palette::{self, Palette}, scheme::UpdateScheme, seeding::{Placement, Seed, Seeding, Symmetry}};
 
-const PALETTE: [egui::Color32; 8] = [
-    egui::Color32::BLACK,       // 0
-    egui::Color32::WHITE,       // 1
-    egui::Color32::DARK_BLUE,   // 2
-    egui::Color32::PURPLE,      // 3
-    egui::Color32::GREEN,       // 4
-    egui::Color32::DARK_GREEN,  // 5
-    egui::Color32::RED,         // 6
-    egui::Color32::GOLD,         // 7
-];
+fn color(s: &StateInfo) -> egui::Color32 {
+    let [r, g, b] = s.color;
+    egui::Color32::from_rgb(r, g, b)
+}
+
+// Action du clic sur la grille.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Tool {
+    Paint,
+    Select,
+    Paste,
+}
+
+// Calcul lancé en tâche de fond (exploration, balayage, propagation de défaut) : `done` sur `total` unités finies.
+struct Job<T> {
+    done: Arc<AtomicUsize>,
+    total: usize,
+    handle: thread::JoinHandle<T>,
+}
+
+impl<T> Job<T> {
+    fn progress(&self) -> f32 {
+        self.done.load(Ordering::Relaxed) as f32 / self.total.max(1) as f32
+    }
+}
+
+type SweepResult = Result<(SweepConfig, Vec<SweepPoint>), String>;
+
+// Courbes sur un même axe (0..max), une valeur par génération.
+fn chart(ui: &mut egui::Ui, title: &str, series: &[(egui::Color32, Vec<f32>)]) {
+    let last: Vec<String> = series.iter().filter_map(|(_, v)| v.last()).map(|v| format!("{v:.0}")).collect();
+    ui.label(format!("{title}: {}", last.join(" / ")));
+
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(ui.available_width(), 70.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, egui::Color32::from_gray(24));
+
+    let max = series.iter().flat_map(|(_, v)| v.iter().copied()).fold(0.0f32, f32::max).max(1e-6);
+    for (c, values) in series {
+        let n = values.len().max(2) - 1;
+        let points = values.iter().enumerate()
+            .map(|(i, &v)| egui::pos2(
+                rect.left() + rect.width() * i as f32 / n as f32,
+                rect.bottom() - rect.height() * v / max,
+            ))
+            .collect();
+        painter.add(egui::Shape::line(points, egui::Stroke::new(1.0, *c)));
+    }
+}
+
+// Densité finale selon α : moyenne reliée, barres d'erreur à ±1 écart type.
+fn sweep_plot(ui: &mut egui::Ui, points: &[SweepPoint]) {
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(ui.available_width(), 180.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, egui::Color32::from_gray(24));
+    let (Some(first), Some(last)) = (points.first(), points.last()) else { return };
+
+    let (a0, a1) = (first.alpha.min(last.alpha), first.alpha.max(last.alpha));
+    let top = points.iter().map(|p| p.mean + p.variance.sqrt()).fold(0.0f64, f64::max).max(1e-6) as f32;
+    let area = rect.shrink(8.0);
+    let pos = |alpha: f32, d: f64| egui::pos2(
+        area.left() + area.width() * if a1 > a0 { (alpha - a0) / (a1 - a0) } else { 0.5 },
+        area.bottom() - area.height() * d as f32 / top,
+    );
+
+    let grey = egui::Stroke::new(1.0, egui::Color32::GRAY);
+    let line = egui::Stroke::new(1.5, egui::Color32::LIGHT_GREEN);
+    painter.add(egui::Shape::line(points.iter().map(|p| pos(p.alpha, p.mean)).collect(), line));
+    for p in points {
+        let sigma = p.variance.sqrt();
+        let (lo, hi) = (pos(p.alpha, (p.mean - sigma).max(0.0)), pos(p.alpha, p.mean + sigma));
+        painter.line_segment([lo, hi], grey);
+        painter.line_segment([lo - egui::vec2(3.0, 0.0), lo + egui::vec2(3.0, 0.0)], grey);
+        painter.line_segment([hi - egui::vec2(3.0, 0.0), hi + egui::vec2(3.0, 0.0)], grey);
+        painter.circle_filled(pos(p.alpha, p.mean), 2.5, egui::Color32::LIGHT_GREEN);
+    }
+    let font = egui::FontId::monospace(10.0);
+    painter.text(rect.left_top() + egui::vec2(4.0, 2.0), egui::Align2::LEFT_TOP, format!("{top:.3}"), font.clone(), egui::Color32::GRAY);
+    painter.text(rect.left_bottom() + egui::vec2(4.0, -2.0), egui::Align2::LEFT_BOTTOM, format!("α {a0}"), font.clone(), egui::Color32::GRAY);
+    painter.text(rect.right_bottom() + egui::vec2(-4.0, -2.0), egui::Align2::RIGHT_BOTTOM, format!("{a1}"), font, egui::Color32::GRAY);
+
+    // point le plus proche du pointeur
+    if let Some(hover) = response.hover_pos()
+        && let Some(p) = points.iter().min_by(|a, b| (pos(a.alpha, 0.0).x - hover.x).abs().total_cmp(&(pos(b.alpha, 0.0).x - hover.x).abs())) {
+        response.on_hover_text(format!("α {}: {:.4} ± {:.4}", p.alpha, p.mean, p.variance.sqrt()));
+    }
+}
+
+// Vignette d'une grille, cases carrées dans au plus `max` points de côté.
+fn thumbnail(ui: &mut egui::Ui, grid: &Grid, states: &[StateInfo], max: f32) {
+    let cell = (max / grid.width().max(grid.height()) as f32).clamp(1.0, 8.0);
+    let size = egui::vec2(cell * grid.width() as f32, cell * grid.height() as f32);
+    let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 0.0, states.first().map_or(egui::Color32::BLACK, color));
+    for y in 0..grid.height() {
+        for x in 0..grid.width() {
+            let v = grid.get(x, y) as usize;
+            if v == 0 { continue; }
+            let min = rect.min + egui::vec2(x as f32 * cell, y as f32 * cell);
+            painter.rect_filled(egui::Rect::from_min_size(min, egui::vec2(cell, cell)), 0.0, states.get(v).map_or(egui::Color32::GRAY, color));
+        }
+    }
+}
 
 
 pub struct App {
     engine: Engine,
     running: bool,
     step_ms: u64,
-    async_fact: f32,
-    selected: String,
+    alpha: f32,
+    custom: String,
+    // noms des automates, calculés une fois (une instance neuve n'est créée qu'à la sélection)
+    names: Vec<String>,
+    // état posé au clic sur la grille
+    paint: u8,
+    // couleurs appliquées par-dessus le schéma de l'automate (vide = schéma)
+    palette: Palette,
+    palettes: Vec<String>,
+    show_stats: bool,
+    // motif choisi dans la bibliothèque fournie (`library::CATALOGUE`)
+    show_library: bool,
+    library: usize,
+
+    // explorateur de règles : intervalle, soupes par règle, résultats triés par colonne
+    show_explore: bool,
+    explore_range: String,
+    explore_soups: usize,
+    explore_job: Option<Job<Vec<RuleReport>>>,
+    explore_reports: Vec<RuleReport>,
+    explore_sort: (usize, bool),
+
+    // balayage de α pour l'automate courant : valeurs, exécutions et pas par valeur
+    show_sweep: bool,
+    sweep_alphas: String,
+    sweep_runs: usize,
+    sweep_steps: u64,
+    sweep_job: Option<Job<SweepResult>>,
+    sweep: Option<(SweepConfig, Vec<SweepPoint>)>,
+
+    // mesures d'ordre de la grille courante, et dernière propagation de défaut
+    show_spatial: bool,
+    // mesures gardées tant que la grille ne change pas : (génération, hachage de la grille)
+    spatial: Option<((u64, u64), SpatialReport)>,
+    damage_steps: usize,
+    damage_job: Option<Job<Result<Vec<f64>, SizeError>>>,
+    damage: Vec<f32>,
+
+    // condition initiale : "init" (celle de l'automate) ou un nom de `Seed::NAMES`
+    seed_kind: &'static str,
+    // côté de la soupe, ou échelle des taches
+    seed_size: usize,
+    seed_density: f32,
+    seed_value: u64,
+    seed_over: bool,
+    // fragment (stamps/*.rle) ou grille (grids/*.cagr)
+    seed_file: String,
+
+    // taille demandée et mode de redimensionnement
+    width: usize,
+    height: usize,
+    resize: Resize,
+
+    tool: Tool,
+    // sélection (x0, y0, x1, y1) inclusive, et case où le glisser a commencé
+    selection: Option<(usize, usize, usize, usize)>,
+    drag_start: Option<(usize, usize)>,
+    clipboard: Option<Grid>,
+    // les 0 du fragment collé ne recouvrent rien
+    transparent: bool,
+    stamp_name: String,
+    stamps: Vec<String>,
+    selected_stamp: String,
 
     // time sync
     last_frame: Instant,
@@ -27,35 +182,133 @@ pub struct App {
 
     save_name: String,
     status: String,
+    finished_seen: Option<u64>,
     grids: Vec<String>,
     selected_grid: String, 
 }
 
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl App {
     pub fn new() -> Self {
         let default = "mazesolver";
         let automaton = automata::by_name(default).unwrap_or_else(|| automata::available().remove(0));
         let grids = crate::io::bin::list_grids().unwrap_or_default();
-        let selected_grid = grids.get(0).cloned().unwrap_or_default();
+        let selected_grid = grids.first().cloned().unwrap_or_default();
         
         Self {
-            async_fact: 1.0,
-            engine: Engine::new(80, 45, 1.0, automaton),
+            alpha: 1.0,
+            engine: Engine::new(80, 45, UpdateScheme::Synchronous, automaton),
             running: false,
             step_ms: 300,
-            selected: default.to_string(),
+            custom: "rule30".to_string(),
+            names: automata::available().iter().map(|a| a.name().to_string()).collect(),
+            paint: 1,
+            palette: Palette::builtin().remove(0),
+            palettes: palette::list(),
+            show_stats: false,
+            show_library: false,
+            library: 0,
+
+            show_explore: false,
+            explore_range: "B3/S23..B36/S236".to_string(),
+            explore_soups: 4,
+            explore_job: None,
+            explore_reports: Vec::new(),
+            explore_sort: (0, false),
+
+            show_sweep: false,
+            sweep_alphas: "0.5:1:0.05".to_string(),
+            sweep_runs: 10,
+            sweep_steps: 1000,
+            sweep_job: None,
+            sweep: None,
+
+            show_spatial: false,
+            spatial: None,
+            damage_steps: 200,
+            damage_job: None,
+            damage: Vec::new(),
+
+            seed_kind: "init",
+            seed_size: 16,
+            seed_density: 0.5,
+            seed_value: 0,
+            seed_over: false,
+            seed_file: String::new(),
+            width: 80,
+            height: 45,
+            resize: Resize::Init,
+
+            tool: Tool::Paint,
+            selection: None,
+            drag_start: None,
+            clipboard: None,
+            transparent: false,
+            stamp_name: "stamp1".to_string(),
+            stamps: rle::list_stamps(),
+            selected_stamp: String::new(),
 
             last_frame: Instant::now(),
             acc: Duration::ZERO,
 
             save_name: "grid1".to_string(),
             status: String::new(),
+            finished_seen: None,
             grids,
             selected_grid,
         }
     }
 }
 
+impl App {
+    // Sélection -> presse-papiers interne, et en RLE dans celui du système.
+    fn copy_selection(&mut self, ctx: &egui::Context) {
+        let g = self.engine.current();
+        let Some((x0, y0, x1, y1)) = self.selection.filter(|s| s.2 < g.width() && s.3 < g.height()) else { return };
+        let fragment = g.region(x0, y0, x1 - x0 + 1, y1 - y0 + 1);
+        ctx.copy_text(rle::to_rle(&fragment, Some(self.engine.automaton().name())));
+        self.status = format!("Copied {}×{}", fragment.width(), fragment.height());
+        self.clipboard = Some(fragment);
+    }
+
+    fn sort_reports(&mut self) {
+        let (column, descending) = self.explore_sort;
+        self.explore_reports.sort_by(|a, b| if descending { b.compare(a, column) } else { a.compare(b, column) });
+    }
+
+    // Graine décrite par les réglages (None : `init` de l'automate).
+    fn seeding(&self) -> Result<Option<Seeding>, String> {
+        let (k, d) = (self.seed_size, self.seed_density);
+        let seed = match self.seed_kind {
+            "init" => return Ok(None),
+            "bernoulli" => Seed::Bernoulli(d),
+            "soup" => Seed::Soup(k, d),
+            "blobs" => Seed::Blobs(k as f32, d),
+            "single" => Seed::Single,
+            "file" => {
+                let name = self.seed_file.trim();
+                let path = if name.ends_with(".rle") { rle::path_in_dir(name) } else { crate::io::bin::path_in_dir(name) };
+                let mut s = Seeding::from_file(path, self.seed_value).map_err(|e| e.to_string())?;
+                s.placement = if self.seed_over { Placement::Over } else { Placement::Instead };
+                return Ok(Some(s));
+            }
+            name => match Symmetry::ALL.into_iter().find(|s| s.name() == name) {
+                Some(sym) => Seed::Symmetric(sym, k, d),
+                None => return Err(format!("Unknown seed: {name}")),
+            },
+        };
+        let mut s = Seeding::new(seed, self.seed_value);
+        s.state = self.paint.max(1);
+        s.placement = if self.seed_over { Placement::Over } else { Placement::Instead };
+        Ok(Some(s))
+    }
+}
+
 impl eframe::App for App {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // --- barre de contrôle
@@ -69,6 +322,31 @@ impl eframe::App for App {
                     self.acc = Duration::ZERO;
                 }
 
+                // Reset button
+                if ui.button("Reset").clicked() {
+                    self.engine.reset();
+                }
+
+                // Back button (historique, ou automates réversibles)
+                if ui.add_enabled(self.engine.can_step_back(), egui::Button::new("Back")).clicked() {
+                    self.running = false;
+                    self.engine.step_back();
+                }
+
+                // Second ordre : x(t+1) = f(x(t)) - x(t-1), recul exact sans historique
+                let mut second = self.engine.is_second_order();
+                if ui.add_enabled(self.engine.automaton().is_local(), egui::Checkbox::new(&mut second, "2nd order")).changed() {
+                    self.engine.set_second_order(second);
+                }
+                if second && ui.button("Verify").clicked()
+                    && let Some(previous) = self.engine.previous() {
+                    self.status = match crate::reversible::verify(self.engine.automaton(), previous, self.engine.current(), 1000) {
+                        Ok(()) => "Reversible over 1000 steps".to_string(),
+                        Err(0) => "Round trip failed".to_string(),
+                        Err(g) => format!("Inversion failed at step {g}"),
+                    };
+                }
+
                 // Step button
                 if ui.button("Step").clicked() {
                     self.engine.step_once();
@@ -80,29 +358,113 @@ impl eframe::App for App {
                 ui.add(egui::Slider::new(&mut self.step_ms, 1..=500).text("ms/step"));
                 ui.separator();
 
+                // Schéma de mise à jour
+                let mut scheme = self.engine.scheme();
+                egui::ComboBox::from_label("Scheme")
+                    .selected_text(scheme.name())
+                    .show_ui(ui, |ui| {
+                        for s in UpdateScheme::ALL {
+                            if ui.selectable_label(scheme.name() == s.name(), s.name()).clicked() {
+                                scheme = UpdateScheme::by_name(s.name(), self.alpha).unwrap_or(s);
+                            }
+                        }
+                    });
+
                 // Async slider
-                ui.label("Synchronism");
-                ui.add(egui::Slider::new(&mut self.async_fact, 0.0..=1.0).text("%"));
-                self.engine.set_async_fact(self.async_fact);
+                if let UpdateScheme::Alpha(_) = scheme {
+                    ui.label("Synchronism");
+                    ui.add(egui::Slider::new(&mut self.alpha, 0.0..=1.0).text("%"));
+                    scheme = UpdateScheme::Alpha(self.alpha);
+                }
+                self.engine.set_scheme(scheme);
                 ui.separator();
 
                 // Selection automate
+                let selected = self.engine.automaton().name().to_string();
                 egui::ComboBox::from_label("Automaton")
-                    .selected_text(&self.selected)
+                    .selected_text(&selected)
                     .show_ui(ui, |ui| {
-                        for a in automata::available() {
-                            let name = a.name().to_string();
-                            if ui.selectable_label(self.selected == name, &name).clicked() {
-                                self.selected = name.clone();
-                                self.engine.set_automaton(a);
+                        for name in &self.names {
+                            if ui.selectable_label(&selected == name, name).clicked()
+                                && let Some(a) = automata::by_name(name) {
+                                match self.engine.set_automaton(a) {
+                                    Ok(()) => {
+                                        self.last_frame = Instant::now();
+                                        self.acc = Duration::ZERO;
+                                    }
+                                    Err(e) => self.status = e.to_string(),
+                                }
+                            }
+                        }
+                    });
+
+                // Automate paramétré (ex: rule57, totalistic3-777)
+                ui.add(egui::TextEdit::singleline(&mut self.custom).desired_width(110.0));
+                if ui.button("Apply").clicked() {
+                    match automata::by_name(self.custom.trim()) {
+                        Some(a) => match self.engine.set_automaton(a) {
+                            Ok(()) => {
+                                self.last_frame = Instant::now();
+                                self.acc = Duration::ZERO;
+                            }
+                            Err(e) => self.status = e.to_string(),
+                        },
+                        // motifs du solveur : on affiche l'erreur de lecture du fichier
+                        None => self.status = match self.custom.trim().strip_prefix("mazesolver:") {
+                            Some(path) => match automata::mazesolver::MazeSolver::from_file(path.trim()) {
+                                Err(e) => format!("Motifs invalides: {e}"),
+                                Ok(_) => format!("Automate inconnu: {}", self.custom.trim()),
+                            },
+                            None => format!("Automate inconnu: {}", self.custom.trim()),
+                        },
+                    }
+                }
+                ui.separator();
+
+                // Génère un labyrinthe (générateur courant, sinon DFS) puis le résout
+                if ui.button("Generate & solve").clicked() {
+                    let generator = Some(selected.as_str())
+                        .filter(|n| n.starts_with("generator") || ["maze", "mazectric"].contains(n) || n.starts_with("cave"))
+                        .and_then(automata::by_name)
+                        .or_else(|| automata::by_name("generator"));
+                    if let (Some(first), Some(then)) = (generator, automata::by_name("mazesolver")) {
+                        match self.engine.chain(first, Until::FinishedOr(5000), then) {
+                            Ok(()) => {
+                                self.running = true;
                                 self.last_frame = Instant::now();
                                 self.acc = Duration::ZERO;
                             }
+                            Err(e) => self.status = e.to_string(),
                         }
-                    });
+                    }
+                }
+                if let Some(next) = self.engine.pending() {
+                    ui.label(format!("→ {next}"));
+                }
+                if let Some(c) = self.engine.cycle() {
+                    ui.label(c.to_string());
+                }
             });
 
             ui.horizontal(|ui| {
+                // Taille de la grille
+                ui.label("Size:");
+                ui.add(egui::DragValue::new(&mut self.width).range(1..=1000));
+                ui.label("×");
+                ui.add(egui::DragValue::new(&mut self.height).range(1..=1000));
+                egui::ComboBox::from_id_salt("resize_combo")
+                    .selected_text(self.resize.name())
+                    .show_ui(ui, |ui| {
+                        for r in Resize::ALL {
+                            ui.selectable_value(&mut self.resize, r, r.name());
+                        }
+                    });
+                if ui.button("Resize").clicked()
+                    && let Err(e) = self.engine.resize(self.width, self.height, self.resize) {
+                    self.status = e.to_string();
+                }
+                ui.separator();
+
                 // Save/Load
                 ui.label("Save:");
                 ui.text_edit_singleline(&mut self.save_name);
@@ -116,7 +478,7 @@ impl eframe::App for App {
                             file.push_str(".cagr");
                         }
                         let path = crate::io::bin::path_in_dir(&file);
-                        match crate::io::bin::save(&path, self.engine.current()) {
+                        match crate::io::bin::save_run(&path, self.engine.current(), self.engine.automaton()) {
                             Ok(()) => {
                                 self.status = format!("Saved: {:?}", path);
                                 self.grids = crate::io::bin::list_grids().unwrap_or_default();
@@ -127,10 +489,39 @@ impl eframe::App for App {
                     }
                 }
 
+                // Exporte le trajet du solveur en JSON à côté des grilles
+                if ui.button("Export path").clicked() {
+                    let report = crate::analysis::path::analyse(self.engine.current());
+                    let file = format!("{}.path.json", self.save_name.trim().trim_end_matches(".cagr"));
+                    let path = crate::io::bin::path_in_dir(&file);
+                    match std::fs::write(&path, report.to_json()) {
+                        Ok(()) => self.status = format!("Exported: {:?}", path),
+                        Err(e) => self.status = format!("Export error: {e:?}"),
+                    }
+                }
+
+                // Image de la grille avec la palette courante
+                if ui.button("Export PNG").clicked() {
+                    let mut states = self.engine.automaton().states();
+                    self.palette.apply(&mut states);
+                    let file = format!("{}.png", self.save_name.trim().trim_end_matches(".cagr"));
+                    let path = crate::io::bin::path_in_dir(&file);
+                    match crate::io::png::save(&path, self.engine.current(), &states, 8) {
+                        Ok(()) => self.status = format!("Exported: {:?}", path),
+                        Err(e) => self.status = format!("Export error: {e:?}"),
+                    }
+                }
+
+                ui.checkbox(&mut self.show_stats, "Stats");
+                ui.checkbox(&mut self.show_library, "Library");
+                ui.checkbox(&mut self.show_explore, "Explore");
+                ui.checkbox(&mut self.show_sweep, "Sweep");
+                ui.checkbox(&mut self.show_spatial, "Spatial");
+
                 if ui.button("Refresh").clicked() {
                     self.grids = crate::io::bin::list_grids().unwrap_or_default();
                     if self.selected_grid.is_empty() {
-                        self.selected_grid = self.grids.get(0).cloned().unwrap_or_default();
+                        self.selected_grid = self.grids.first().cloned().unwrap_or_default();
                     }
                 }
 
@@ -147,11 +538,26 @@ impl eframe::App for App {
                         self.status = "Aucune grille".to_string();
                     } else {
                         let path = crate::io::bin::path_in_dir(&self.selected_grid);
-                        match crate::io::bin::load(&path) {
-                            Ok(g) => {
-                                self.engine.set_grid(g);
-                                self.engine.soft_init();
-                                self.status = format!("Loaded: {:?}", path);
+                        match crate::io::bin::load_run(&path) {
+                            Ok((g, saved)) => {
+                                // reprend l'automate sauvegardé si possible, sinon garde l'actuel
+                                let restored = saved.and_then(|s| {
+                                    let mut a = automata::by_name(&s.name)?;
+                                    a.load_state(&s.state).then_some(a)
+                                });
+                                let loaded = match restored {
+                                    Some(a) => self.engine.restore(g, a),
+                                    None => self.engine.set_grid(g).map(|()| self.engine.soft_init()),
+                                };
+                                match loaded {
+                                    Ok(()) => {
+                                        // la grille chargée impose sa taille
+                                        self.width = self.engine.current().width();
+                                        self.height = self.engine.current().height();
+                                        self.status = format!("Loaded {}×{}: {:?}", self.width, self.height, path);
+                                    }
+                                    Err(e) => self.status = format!("Load error: {e}"),
+                                }
                             }
                             Err(e) => self.status = format!("Load error: {e:?} (path={:?})", path),
                         }
@@ -163,36 +569,584 @@ impl eframe::App for App {
                 }
 
             });
+
+            ui.horizontal(|ui| {
+                // Outils : peinture, sélection, collage
+                ui.selectable_value(&mut self.tool, Tool::Paint, "Paint");
+                ui.selectable_value(&mut self.tool, Tool::Select, "Select");
+                ui.add_enabled_ui(self.clipboard.is_some(), |ui| {
+                    ui.selectable_value(&mut self.tool, Tool::Paste, "Paste");
+                });
+                ui.separator();
+
+                if ui.add_enabled(self.selection.is_some(), egui::Button::new("Copy")).clicked() {
+                    self.copy_selection(ui.ctx());
+                }
+                if ui.add_enabled(self.clipboard.is_some(), egui::Button::new("Rotate")).clicked() {
+                    self.clipboard = self.clipboard.as_ref().map(|c| c.transformed(ROTATE));
+                }
+                if ui.add_enabled(self.clipboard.is_some(), egui::Button::new("Flip")).clicked() {
+                    self.clipboard = self.clipboard.as_ref().map(|c| c.transformed(FLIP));
+                }
+                ui.checkbox(&mut self.transparent, "Transparent");
+                if let Some(c) = &self.clipboard {
+                    ui.label(format!("clipboard {}×{}", c.width(), c.height()));
+                }
+                ui.separator();
+
+                // Bibliothèque de fragments (stamps/*.rle)
+                ui.add(egui::TextEdit::singleline(&mut self.stamp_name).desired_width(90.0));
+                if ui.add_enabled(self.clipboard.is_some(), egui::Button::new("Save stamp")).clicked()
+                    && let Some(c) = &self.clipboard {
+                    match rle::save_stamp(self.stamp_name.trim(), c, Some(self.engine.automaton().name())) {
+                        Ok(path) => {
+                            self.status = format!("Saved: {:?}", path);
+                            self.stamps = rle::list_stamps();
+                            self.selected_stamp = self.stamp_name.trim().to_string();
+                        }
+                        Err(e) => self.status = format!("Save error: {e:?}"),
+                    }
+                }
+                egui::ComboBox::from_id_salt("stamp_combo")
+                    .selected_text(if self.selected_stamp.is_empty() { "(none)" } else { &self.selected_stamp })
+                    .show_ui(ui, |ui| {
+                        for name in &self.stamps {
+                            ui.selectable_value(&mut self.selected_stamp, name.clone(), name);
+                        }
+                    });
+                if ui.add_enabled(!self.selected_stamp.is_empty(), egui::Button::new("Use stamp")).clicked() {
+                    match rle::load_stamp(&self.selected_stamp) {
+                        Ok(g) => {
+                            self.clipboard = Some(g);
+                            self.tool = Tool::Paste;
+                        }
+                        Err(e) => self.status = e.to_string(),
+                    }
+                }
+            });
+
+            ui.horizontal(|ui| {
+                // Condition initiale, appliquée à chaque Reset
+                ui.label("Seed");
+                egui::ComboBox::from_id_salt("seed_combo")
+                    .selected_text(self.seed_kind)
+                    .show_ui(ui, |ui| {
+                        for name in std::iter::once("init").chain(Seed::NAMES) {
+                            ui.selectable_value(&mut self.seed_kind, name, name);
+                        }
+                    });
+                match self.seed_kind {
+                    "init" | "single" => {}
+                    "file" => {
+                        ui.add(egui::TextEdit::singleline(&mut self.seed_file).hint_text("name.rle / name.cagr").desired_width(120.0));
+                    }
+                    kind => {
+                        if kind != "bernoulli" {
+                            let label = if kind == "blobs" { "scale " } else { "size " };
+                            ui.add(egui::DragValue::new(&mut self.seed_size).range(1..=1000).prefix(label));
+                        }
+                        ui.add(egui::Slider::new(&mut self.seed_density, 0.0..=1.0).text("density"));
+                    }
+                }
+                ui.add(egui::DragValue::new(&mut self.seed_value).prefix("seed "));
+                if ui.button("Random").clicked() {
+                    self.seed_value = rand::random();
+                }
+                ui.checkbox(&mut self.seed_over, "Over init");
+                if ui.button("Apply seed").clicked() {
+                    match self.seeding() {
+                        Ok(s) => {
+                            self.engine.set_seeding(s);
+                            self.running = false;
+                        }
+                        Err(e) => self.status = e,
+                    }
+                }
+                if let Some(s) = self.engine.seeding() {
+                    ui.label(format!("current: {} #{}", s.seed.name(), s.value));
+                }
+            });
         });
 
+        // --- légende : états de l'automate courant, clic pour choisir l'état à peindre
+        let mut states = self.engine.automaton().states();
+        self.palette.apply(&mut states);
+        if states.get(self.paint as usize).is_none_or(|s| !s.paintable) {
+            self.paint = states.iter().rposition(|s| s.paintable).unwrap_or(0) as u8;
+        }
+        egui::SidePanel::right("legend").show(ctx, |ui| {
+            ui.heading("States");
+            for (i, s) in states.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    // éditer une couleur la fixe dans la palette courante
+                    let mut c = s.color;
+                    if ui.color_edit_button_srgb(&mut c).changed() {
+                        if self.palette.colors.len() <= i {
+                            let base = states.iter().skip(self.palette.colors.len()).take(i + 1 - self.palette.colors.len());
+                            self.palette.colors.extend(base.map(|s| s.color));
+                        }
+                        self.palette.colors[i] = c;
+                    }
+
+                    let label = format!("{i} {}", s.name);
+                    let resp = if s.paintable {
+                        ui.selectable_label(self.paint as usize == i, label)
+                    } else {
+                        ui.add_enabled(false, egui::Label::new(label))
+                    };
+                    if resp.on_hover_text(&s.description).clicked() && s.paintable {
+                        self.paint = i as u8;
+                    }
+                });
+            }
+
+            // --- éditeur de palette
+            ui.separator();
+            ui.heading("Palette");
+            egui::ComboBox::from_id_salt("palette_combo")
+                .selected_text(&self.palette.name)
+                .show_ui(ui, |ui| {
+                    for name in &self.palettes {
+                        if ui.selectable_label(&self.palette.name == name, name).clicked() {
+                            match Palette::by_name(name) {
+                                Ok(p) => self.palette = p,
+                                Err(e) => self.status = format!("Palette invalide: {e}"),
+                            }
+                        }
+                    }
+                });
+            ui.horizontal(|ui| {
+                ui.label("Name");
+                ui.add(egui::TextEdit::singleline(&mut self.palette.name).desired_width(90.0));
+            });
+            ui.horizontal(|ui| {
+                // dégradé du premier au dernier état, pour les règles à nombreux états
+                if ui.button("Gradient").clicked()
+                    && let (Some(first), Some(last)) = (states.first(), states.last()) {
+                    self.palette.colors = palette::gradient(&[first.color, last.color], states.len());
+                }
+                if ui.button("Clear").clicked() {
+                    self.palette.colors.clear();
+                }
+                if ui.button("Save").clicked() {
+                    match self.palette.save() {
+                        Ok(path) => {
+                            self.status = format!("Saved: {:?}", path);
+                            self.palettes = palette::list();
+                        }
+                        Err(e) => self.status = format!("Save error: {e}"),
+                    }
+                }
+            });
+        });
+
+        // --- statistiques de l'engine
+        if self.show_stats {
+            egui::SidePanel::left("stats").default_width(240.0).show(ctx, |ui| {
+                let samples = self.engine.stats().samples();
+                let series = |f: &dyn Fn(&crate::stats::Sample) -> f32| samples.iter().map(f).collect::<Vec<f32>>();
+                let white = egui::Color32::WHITE;
+
+                ui.heading("Stats");
+                let population: Vec<_> = (1..states.len())
+                    .map(|i| (color(&states[i]), series(&|s| s.counts.get(i).copied().unwrap_or(0) as f32)))
+                    .collect();
+                chart(ui, "Population", &population);
+                chart(ui, "Births / deaths", &[
+                    (egui::Color32::LIGHT_GREEN, series(&|s| s.births as f32)),
+                    (egui::Color32::LIGHT_RED, series(&|s| s.deaths as f32)),
+                ]);
+                chart(ui, "Density %", &[(white, series(&|s| 100.0 * s.density))]);
+                chart(ui, "Bounding box w / h", &[
+                    (egui::Color32::LIGHT_BLUE, series(&|s| s.bbox.map_or(0.0, |b| (b.2 - b.0 + 1) as f32))),
+                    (egui::Color32::YELLOW, series(&|s| s.bbox.map_or(0.0, |b| (b.3 - b.1 + 1) as f32))),
+                ]);
+
+                // mesures de l'automate courant (ex: solveur : explorées, trajet)
+                if let Some(last) = samples.back() {
+                    for (name, _) in &last.metrics {
+                        chart(ui, name, &[(white, series(&|s| {
+                            s.metrics.iter().find(|(n, _)| n == name).map_or(0.0, |m| m.1 as f32)
+                        }))]);
+                    }
+                }
+            });
+        }
+
+        // --- bibliothèque de motifs : au presse-papiers, ou sur une grille neuve à sa taille
+        if self.show_library {
+            let mut open = true;
+            egui::Window::new("Library").open(&mut open).default_width(320.0).show(ctx, |ui| {
+                egui::ScrollArea::vertical().max_height(260.0).show(ui, |ui| {
+                    for category in Category::ALL {
+                        egui::CollapsingHeader::new(category.name()).default_open(true).show(ui, |ui| {
+                            for (i, e) in library::CATALOGUE.iter().enumerate().filter(|(_, e)| e.category == category) {
+                                ui.selectable_value(&mut self.library, i, e.name);
+                            }
+                        });
+                    }
+                });
+                ui.separator();
+
+                let Some(entry) = library::CATALOGUE.get(self.library) else { return };
+                let Some(automaton) = automata::by_name(entry.automaton) else { return };
+                let pattern = entry.grid();
+                ui.heading(entry.name);
+                ui.label(format!("{} · {}×{}", entry.automaton, pattern.width(), pattern.height()));
+                if let Some(p) = entry.period {
+                    ui.label(format!("period {p}"));
+                }
+                ui.label(entry.description);
+                thumbnail(ui, &pattern, &automaton.states(), 160.0);
+
+                ui.horizontal(|ui| {
+                    if ui.button("To clipboard").clicked() {
+                        self.clipboard = Some(pattern.clone());
+                        self.tool = Tool::Paste;
+                    }
+                    if ui.button("New grid").clicked() {
+                        match self.engine.restore(entry.fresh_grid(), automaton) {
+                            Ok(()) => {
+                                self.engine.soft_init();
+                                self.running = false;
+                                self.width = self.engine.current().width();
+                                self.height = self.engine.current().height();
+                                self.status = format!("{}: {}×{}", entry.name, self.width, self.height);
+                            }
+                            Err(e) => self.status = e.to_string(),
+                        }
+                    }
+                });
+            });
+            self.show_library &= open;
+        }
+
+        // --- explorateur de règles B/S : table triable, un clic charge la règle
+        if self.explore_job.as_ref().is_some_and(|j| j.handle.is_finished())
+            && let Some(job) = self.explore_job.take() {
+            match job.handle.join() {
+                Ok(reports) => {
+                    self.explore_reports = reports;
+                    self.sort_reports();
+                }
+                Err(_) => self.status = "Explore: thread panicked".to_string(),
+            }
+        }
+        if self.show_explore {
+            let mut open = true;
+            egui::Window::new("Explore").open(&mut open).default_width(560.0).show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut self.explore_range).desired_width(180.0));
+                    ui.add(egui::DragValue::new(&mut self.explore_soups).range(1..=64).prefix("soups "));
+                    match &self.explore_job {
+                        Some(job) => {
+                            ui.add(egui::ProgressBar::new(job.progress()).desired_width(120.0));
+                            ctx.request_repaint_after(Duration::from_millis(100));
+                        }
+                        None => if ui.button("Run").clicked() {
+                            match RuleRange::parse(self.explore_range.trim()) {
+                                Ok(range) => {
+                                    let mut config = ExploreConfig::new(range);
+                                    config.soups = self.explore_soups;
+                                    let done = Arc::new(AtomicUsize::new(0));
+                                    let counter = done.clone();
+                                    let handle = thread::spawn(move || explore::run(&config, &counter));
+                                    self.explore_job = Some(Job { done, total: range.count(), handle });
+                                }
+                                Err(e) => self.status = format!("Explore: {e}"),
+                            }
+                        },
+                    }
+                });
+
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    egui::Grid::new("explore_table").striped(true).show(ui, |ui| {
+                        // en-têtes : clic pour trier, second clic pour inverser
+                        for (i, name) in RuleReport::COLUMNS.iter().enumerate() {
+                            let (column, descending) = self.explore_sort;
+                            let arrow = if column != i { "" } else if descending { " ▼" } else { " ▲" };
+                            if ui.button(format!("{name}{arrow}")).clicked() {
+                                self.explore_sort = (i, column == i && !descending);
+                                self.sort_reports();
+                            }
+                        }
+                        ui.label("");
+                        ui.end_row();
+
+                        for r in &self.explore_reports {
+                            ui.monospace(r.rule.to_string());
+                            ui.label(r.behaviour.name());
+                            ui.label(format!("{:.2}", r.growth));
+                            ui.label(format!("{:.3}", r.density));
+                            ui.label(format!("{:.3}", r.entropy));
+                            ui.label(r.period.map_or(String::new(), |p| p.to_string()));
+                            ui.label(format!("{:.0}", r.generations));
+                            if ui.button("Load").clicked() {
+                                let name = r.rule.to_string();
+                                match automata::by_name(&name).map(|a| self.engine.set_automaton(a)) {
+                                    Some(Ok(())) => {
+                                        self.custom = name;
+                                        self.running = false;
+                                        self.status = format!("Loaded {}", r.rule);
+                                    }
+                                    Some(Err(e)) => self.status = e.to_string(),
+                                    None => self.status = format!("Unknown rule {name}"),
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+            self.show_explore &= open;
+        }
+
+        // --- balayage de α : densité finale de l'automate courant en schéma α-asynchrone
+        if self.sweep_job.as_ref().is_some_and(|j| j.handle.is_finished())
+            && let Some(job) = self.sweep_job.take() {
+            match job.handle.join() {
+                Ok(Ok(result)) => self.sweep = Some(result),
+                Ok(Err(e)) => self.status = format!("Sweep: {e}"),
+                Err(_) => self.status = "Sweep: thread panicked".to_string(),
+            }
+        }
+        if self.show_sweep {
+            let mut open = true;
+            egui::Window::new("Sweep").open(&mut open).default_width(420.0).show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut self.sweep_alphas).desired_width(120.0));
+                    ui.add(egui::DragValue::new(&mut self.sweep_runs).range(1..=200).prefix("runs "));
+                    ui.add(egui::DragValue::new(&mut self.sweep_steps).range(1..=100_000).prefix("steps "));
+                });
+                ui.horizontal(|ui| {
+                    match &self.sweep_job {
+                        Some(job) => {
+                            ui.add(egui::ProgressBar::new(job.progress()).desired_width(160.0));
+                            ctx.request_repaint_after(Duration::from_millis(100));
+                        }
+                        None => if ui.button("Run").clicked() {
+                            match sweep::parse_alphas(&self.sweep_alphas) {
+                                Ok(alphas) => {
+                                    let g = self.engine.current();
+                                    let mut config = SweepConfig::new(self.engine.automaton().name(), alphas);
+                                    (config.width, config.height) = (g.width(), g.height());
+                                    config.runs = self.sweep_runs;
+                                    config.steps = self.sweep_steps;
+                                    // la graine réglée plus haut, sinon une soupe uniforme à 0.5
+                                    if let Ok(Some(s)) = self.seeding() {
+                                        config.initial = s.seed;
+                                        config.seed = s.value;
+                                    }
+                                    let total = config.alphas.len() * config.runs;
+                                    let done = Arc::new(AtomicUsize::new(0));
+                                    let counter = done.clone();
+                                    let handle = thread::spawn(move || sweep::run(&config, &counter).map(|points| (config, points)));
+                                    self.sweep_job = Some(Job { done, total, handle });
+                                }
+                                Err(e) => self.status = format!("Sweep: {e}"),
+                            }
+                        },
+                    }
+                    if let Some((config, points)) = &self.sweep
+                        && ui.button("Export CSV").clicked() {
+                        let path = crate::io::bin::path_in_dir("sweep.csv");
+                        match std::fs::write(&path, sweep::to_csv(config, points)) {
+                            Ok(()) => self.status = format!("Exported: {:?}", path),
+                            Err(e) => self.status = format!("Export error: {e}"),
+                        }
+                    }
+                });
+
+                if let Some((config, points)) = &self.sweep {
+                    ui.label(format!("{}: {} runs × {} steps, {}×{}", config.automaton, config.runs, config.steps, config.width, config.height));
+                    sweep_plot(ui, points);
+                }
+            });
+            self.show_sweep &= open;
+        }
+
+        // --- ordre / désordre de la grille courante
+        if self.damage_job.as_ref().is_some_and(|j| j.handle.is_finished())
+            && let Some(job) = self.damage_job.take() {
+            match job.handle.join() {
+                Ok(Ok(distances)) => self.damage = distances.into_iter().map(|d| 100.0 * d as f32).collect(),
+                Ok(Err(e)) => self.status = format!("Damage: {e}"),
+                Err(_) => self.status = "Damage: thread panicked".to_string(),
+            }
+        }
+        if self.show_spatial {
+            let g = self.engine.current();
+            let key = (self.engine.generation(), crate::cycle::hash_grid(g, &[]));
+            if self.spatial.as_ref().is_none_or(|(k, _)| *k != key) {
+                self.spatial = Some((key, SpatialReport::measure(g)));
+            }
+
+            let mut open = true;
+            egui::Window::new("Spatial").open(&mut open).default_width(280.0).show(ctx, |ui| {
+                let g = self.engine.current();
+                let Some((_, r)) = &self.spatial else { return };
+                let white = egui::Color32::WHITE;
+                ui.label(format!("cell entropy: {:.3} bits", r.cell_entropy));
+                ui.label(format!("block entropy 2×2 / 3×3: {:.3} / {:.3} bits per cell", r.block_entropy2, r.block_entropy3));
+                ui.label(format!("correlation length: {}", r.correlation_length.map_or("—".to_string(), |l| l.to_string())));
+                chart(ui, "Correlation r=1..", &[(white, r.correlation.iter().map(|&c| c.max(0.0) as f32).collect())]);
+                ui.label(format!("clusters: {}, largest {}, mean {:.1}", r.clusters.count, r.clusters.largest, r.clusters.mean));
+                let mut sizes = vec![0.0f32; r.clusters.largest.min(64)];
+                for &(size, n) in &r.clusters.histogram {
+                    if let Some(v) = sizes.get_mut(size - 1) {
+                        *v = n as f32;
+                    }
+                }
+                chart(ui, "Clusters by size", &[(egui::Color32::LIGHT_BLUE, sizes)]);
+                ui.separator();
+
+                // deux copies de la grille, une cellule d'écart au centre, même schéma et mêmes tirages
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut self.damage_steps).range(1..=5000).suffix(" steps"));
+                    match &self.damage_job {
+                        Some(job) => {
+                            ui.add(egui::ProgressBar::new(job.progress()).desired_width(120.0));
+                            ctx.request_repaint_after(Duration::from_millis(100));
+                        }
+                        None => if ui.button("Damage").clicked() {
+                            let (automaton, grid, scheme) = (self.engine.automaton().box_clone(), g.clone(), self.engine.scheme());
+                            let (steps, center) = (self.damage_steps, (g.width() / 2, g.height() / 2));
+                            let done = Arc::new(AtomicUsize::new(0));
+                            let counter = done.clone();
+                            let handle = thread::spawn(move || {
+                                spatial::damage_spreading(automaton.as_ref(), &grid, scheme, rand::random(), center, steps, &counter)
+                            });
+                            self.damage_job = Some(Job { done, total: steps, handle });
+                        },
+                    }
+                });
+                if !self.damage.is_empty() {
+                    chart(ui, "Hamming distance %", &[(egui::Color32::LIGHT_RED, self.damage.clone())]);
+                }
+            });
+            self.show_spatial &= open;
+        }
+
+        // --- presse-papiers système : Ctrl+C copie la sélection en RLE, Ctrl+V colle du RLE
+        if !ctx.wants_keyboard_input() {
+            let events = ctx.input(|i| i.events.clone());
+            for e in events {
+                match e {
+                    egui::Event::Copy if self.selection.is_some() => self.copy_selection(ctx),
+                    egui::Event::Paste(text) => match rle::from_rle(&text) {
+                        Ok(g) => {
+                            self.clipboard = Some(g);
+                            self.tool = Tool::Paste;
+                        }
+                        Err(e) => self.status = e.to_string(),
+                    },
+                    _ => {}
+                }
+            }
+        }
+
         // rendu 
         egui::CentralPanel::default().show(ctx, |ui| {
-            let g = self.engine.current();
             let avail = ui.available_size();
+            let g = self.engine.current();
             let cell_w = (avail.x / g.width() as f32).floor().max(1.0);
             let cell_h = (avail.y / g.height() as f32).floor().max(1.0);
             let cell = cell_w.min(cell_h);
 
-            let (rect, _resp) = ui.allocate_exact_size(
+            let (rect, resp) = ui.allocate_exact_size(
                 egui::vec2(cell * g.width() as f32, cell * g.height() as f32),
-                egui::Sense::hover(),
+                egui::Sense::click_and_drag(),
             );
             let painter = ui.painter_at(rect);
+            let background = states.first().map_or(egui::Color32::BLACK, color);
+            painter.rect_filled(rect, 0.0, background);
 
             for y in 0..g.height() {
                 for x in 0..g.width() {
                     let v = g.get(x, y) as usize;
                     if v == 0 { continue; }
 
-                    let color = PALETTE.get(v).copied().unwrap_or(egui::Color32::GRAY);
+                    let color = states.get(v).map_or(egui::Color32::GRAY, color);
 
                     let min = rect.min + egui::vec2(x as f32 * cell, y as f32 * cell);
                     let r = egui::Rect::from_min_size(min, egui::vec2(cell, cell));
                     painter.rect_filled(r, 0.0, color);
                 }
             }
+
+            // case sous le pointeur
+            let (gw, gh) = (g.width(), g.height());
+            let to_cell = |p: egui::Pos2| {
+                let d = (p - rect.min) / cell;
+                (d.x >= 0.0 && d.y >= 0.0).then(|| ((d.x as usize).min(gw - 1), (d.y as usize).min(gh - 1)))
+            };
+            let cell_rect = |x0: usize, y0: usize, w: usize, h: usize| egui::Rect::from_min_size(
+                rect.min + egui::vec2(x0 as f32 * cell, y0 as f32 * cell),
+                egui::vec2(w as f32 * cell, h as f32 * cell),
+            );
+            let outline = egui::Stroke::new(1.5, egui::Color32::from_rgb(255, 160, 0));
+
+            match self.tool {
+                Tool::Paint => {
+                    if (resp.clicked() || resp.dragged())
+                        && let Some((x, y)) = resp.interact_pointer_pos().and_then(to_cell) {
+                        self.engine.paint(x, y, self.paint);
+                    }
+                }
+                Tool::Select => {
+                    if resp.drag_started() {
+                        self.drag_start = resp.interact_pointer_pos().and_then(to_cell);
+                    }
+                    if (resp.dragged() || resp.clicked())
+                        && let Some((x, y)) = resp.interact_pointer_pos().and_then(to_cell) {
+                        let (sx, sy) = self.drag_start.filter(|_| resp.dragged()).unwrap_or((x, y));
+                        self.selection = Some((sx.min(x), sy.min(y), sx.max(x), sy.max(y)));
+                    }
+                }
+                Tool::Paste => {
+                    // aperçu du fragment sous le pointeur, collé au clic
+                    if let (Some(c), Some((x, y))) = (&self.clipboard, resp.hover_pos().and_then(to_cell)) {
+                        for j in 0..c.height() {
+                            for i in 0..c.width() {
+                                let v = c.get(i, j) as usize;
+                                if v == 0 { continue; }
+                                let col = states.get(v).map_or(egui::Color32::GRAY, color);
+                                painter.rect_filled(cell_rect((x + i) % gw, (y + j) % gh, 1, 1), 0.0, col.gamma_multiply(0.6));
+                            }
+                        }
+                        painter.rect_stroke(cell_rect(x, y, c.width(), c.height()), 0.0, outline, egui::StrokeKind::Inside);
+                    }
+                    if resp.clicked()
+                        && let (Some(c), Some((x, y))) = (self.clipboard.clone(), resp.interact_pointer_pos().and_then(to_cell)) {
+                        self.engine.stamp(&c, x, y, self.transparent);
+                    }
+                }
+            }
+
+            if let Some((x0, y0, x1, y1)) = self.selection.filter(|s| s.2 < gw && s.3 < gh) {
+                painter.rect_stroke(cell_rect(x0, y0, x1 - x0 + 1, y1 - y0 + 1), 0.0, outline, egui::StrokeKind::Inside);
+            }
         });
 
+        // --- fin signalée par l'automate : l'engine est en pause, on l'annonce une fois
+        if self.engine.finished() != self.finished_seen {
+            self.finished_seen = self.engine.finished();
+            if let Some(n) = self.finished_seen {
+                self.running = false;
+                let name = self.engine.automaton().name();
+                self.status = if name.starts_with("mazesolver") {
+                    let report = crate::analysis::path::analyse(self.engine.current());
+                    match (report.length(), report.optimal) {
+                        (Some(l), true) => format!("Solved in {n} steps, path {l} (optimal)"),
+                        (Some(l), false) => format!("Solved in {n} steps, path {l} (shortest {:?})", report.shortest),
+                        (None, _) => format!("Solved in {n} steps, no connected path"),
+                    }
+                } else {
+                    format!("{name}: finished in {n} steps")
+                };
+            }
+        }
+
         // --- simulateur stable (accumulateur)
         let now = Instant::now();
         let dt = now.duration_since(self.last_frame);